@@ -1,16 +1,24 @@
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, Signature, SudoConfig,
-	SystemConfig, WASM_BINARY,
+	AccountId, AuraConfig, Balance, BalancesConfig, DexConfig, Erc1155Config, GenesisConfig,
+	GrandpaConfig, NativeTokenId, Signature, SudoConfig, SystemConfig, WASM_BINARY,
 };
 use sc_service::ChainType;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_core::{sr25519, Pair, Public};
 use sp_finality_grandpa::AuthorityId as GrandpaId;
-use sp_runtime::traits::{IdentifyAccount, Verify};
+use sp_runtime::{
+	traits::{IdentifyAccount, Verify},
+	Perbill,
+};
 
 // The URL for the telemetry server.
 // const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
 
+/// Decimals of the demo ERC1155 tokens.
+const DECIMALS: u8 = 6;
+/// One whole ERC1155 token in base units.
+const TOKEN: Balance = 10u128.pow(DECIMALS as u32);
+
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
 pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig>;
 
@@ -59,6 +67,11 @@ pub fn development_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
 				],
+				// ERC1155 token holders
+				vec![
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					get_account_id_from_seed::<sr25519::Public>("Bob"),
+				],
 				true,
 			)
 		},
@@ -106,6 +119,11 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 				],
+				// ERC1155 token holders
+				vec![
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					get_account_id_from_seed::<sr25519::Public>("Bob"),
+				],
 				true,
 			)
 		},
@@ -122,12 +140,46 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 	))
 }
 
+/// ERC1155 tokens 1, 2 and 3 administered by the first of `holders`, with the supply split
+/// evenly between `holders`.
+fn demo_tokens(holders: &[AccountId]) -> Erc1155Config {
+	let token_ids = [1, 2, 3];
+	let balance = 1_000_000 * TOKEN;
+	let supply = balance * holders.len() as Balance;
+	Erc1155Config {
+		tokens: token_ids.iter().map(|id| (*id, holders[0].clone(), supply, DECIMALS)).collect(),
+		balances: holders
+			.iter()
+			.flat_map(|holder| token_ids.iter().map(move |id| (holder.clone(), *id, balance)))
+			.collect(),
+		approvals: vec![],
+	}
+}
+
+/// DEX pools `owner` provides the initial liquidity of, including the pool fees can be paid in
+/// token 1 through.
+fn demo_pools(owner: AccountId) -> Vec<(AccountId, u32, Balance, u32, Balance, Perbill)> {
+	vec![
+		(
+			owner.clone(),
+			NativeTokenId::get(),
+			1 << 50,
+			1,
+			10_000 * TOKEN,
+			Perbill::from_percent(99),
+		),
+		(owner.clone(), 1, 10_000 * TOKEN, 2, 20_000 * TOKEN, Perbill::from_percent(99)),
+		(owner, 1, 10_000 * TOKEN, 3, 5_000 * TOKEN, Perbill::from_perthousand(997)),
+	]
+}
+
 /// Configure initial storage state for FRAME modules.
 fn testnet_genesis(
 	wasm_binary: &[u8],
 	initial_authorities: Vec<(AuraId, GrandpaId)>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
+	token_holders: Vec<AccountId>,
 	_enable_println: bool,
 ) -> GenesisConfig {
 	GenesisConfig {
@@ -150,5 +202,7 @@ fn testnet_genesis(
 			key: root_key,
 		},
 		transaction_payment: Default::default(),
+		erc1155: demo_tokens(&token_holders),
+		dex: DexConfig { pools: demo_pools(token_holders[0].clone()) },
 	}
 }
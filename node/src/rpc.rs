@@ -7,12 +7,20 @@
 
 use std::sync::Arc;
 
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result as RpcResult};
+use jsonrpc_derive::rpc;
 use node_template_runtime::{opaque::Block, AccountId, Balance, Index};
+use pallet_erc1155::TokenMetadata;
+use pallet_erc1155_runtime_api::Erc1155Api;
 pub use sc_rpc_api::DenyUnsafe;
 use sc_transaction_pool_api::TransactionPool;
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+/// Id of an ERC1155 token.
+pub type TokenId = u32;
 
 /// Full client dependencies.
 pub struct FullDeps<C, P> {
@@ -32,6 +40,7 @@ where
 	C: Send + Sync + 'static,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
+	C::Api: Erc1155Api<Block, AccountId, TokenId, Balance, TokenMetadata<Vec<u8>>>,
 	C::Api: BlockBuilder<Block>,
 	P: TransactionPool + 'static,
 {
@@ -45,6 +54,8 @@ where
 
 	io.extend_with(TransactionPaymentApi::to_delegate(TransactionPayment::new(client.clone())));
 
+	io.extend_with(Erc1155RpcApi::to_delegate(Erc1155Rpc::new(client.clone())));
+
 	// Extend this RPC with a custom API by using the following syntax.
 	// `YourRpcStruct` should have a reference to a client, which is needed
 	// to call into the runtime.
@@ -52,3 +63,88 @@ where
 
 	io
 }
+
+/// ERC1155 queries answered by the `Erc1155Api` runtime API.
+#[rpc(server)]
+pub trait Erc1155RpcApi<BlockHash> {
+	/// Up to `limit` of the tokens `account` holds as `(token_id, balance)`, skipping the first
+	/// `offset`.
+	#[rpc(name = "erc1155_tokensOf")]
+	fn tokens_of(
+		&self,
+		account: AccountId,
+		offset: u32,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<(TokenId, Balance)>>;
+
+	/// Up to `limit` of the holders of `token_id` as `(account, balance)`, skipping the first
+	/// `offset`.
+	#[rpc(name = "erc1155_holdersOf")]
+	fn holders_of(
+		&self,
+		token_id: TokenId,
+		offset: u32,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<(AccountId, Balance)>>;
+}
+
+/// Implementation of `Erc1155RpcApi` querying the runtime of `client`.
+pub struct Erc1155Rpc<C> {
+	client: Arc<C>,
+}
+
+impl<C> Erc1155Rpc<C> {
+	/// Create the ERC1155 RPC handler.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client }
+	}
+
+	fn block_id(&self, at: Option<<Block as BlockT>::Hash>) -> BlockId<Block>
+	where
+		C: HeaderBackend<Block>,
+	{
+		BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash))
+	}
+}
+
+impl<C> Erc1155RpcApi<<Block as BlockT>::Hash> for Erc1155Rpc<C>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: Erc1155Api<Block, AccountId, TokenId, Balance, TokenMetadata<Vec<u8>>>,
+{
+	fn tokens_of(
+		&self,
+		account: AccountId,
+		offset: u32,
+		limit: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<(TokenId, Balance)>> {
+		self.client
+			.runtime_api()
+			.tokens_of(&self.block_id(at), account, offset, limit)
+			.map_err(runtime_error)
+	}
+
+	fn holders_of(
+		&self,
+		token_id: TokenId,
+		offset: u32,
+		limit: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<(AccountId, Balance)>> {
+		self.client
+			.runtime_api()
+			.holders_of(&self.block_id(at), token_id, offset, limit)
+			.map_err(runtime_error)
+	}
+}
+
+fn runtime_error(error: sp_api::ApiError) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(1),
+		message: "Unable to query the ERC1155 runtime API.".into(),
+		data: Some(error.to_string().into()),
+	}
+}
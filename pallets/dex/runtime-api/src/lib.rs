@@ -0,0 +1,42 @@
+//! Runtime API of the DEX pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+
+sp_api::decl_runtime_apis! {
+	pub trait DexApi<AccountId, PoolId, TokenId, Balance, Quote> where
+		AccountId: Codec,
+		PoolId: Codec,
+		TokenId: Codec,
+		Balance: Codec,
+		Quote: Codec,
+	{
+		/// Quote of selling `amount` of `token_id` to `pool_id`, `None` if the pool cannot quote it.
+		fn quote_buy(pool_id: PoolId, token_id: TokenId, amount: Balance) -> Option<Quote>;
+
+		/// Amount of `token_id` which has to be sold to `pool_id` to buy at least `amount_out` of
+		/// the paired token.
+		fn quote_exact_out(
+			pool_id: PoolId,
+			token_id: TokenId,
+			amount_out: Balance,
+		) -> Option<Balance>;
+
+		/// Quote of the swap made when depositing `amount` of `token_id` alone to `pool_id`.
+		fn quote_deposit_single_token(
+			pool_id: PoolId,
+			token_id: TokenId,
+			amount: Balance,
+		) -> Option<Quote>;
+
+		/// Quote of the swap made when `owner` withdraws `share_percent` of its liquidity in
+		/// `pool_id` as `token_id` only.
+		fn quote_withdraw_single_token(
+			pool_id: PoolId,
+			owner: AccountId,
+			token_id: TokenId,
+			share_percent: u32,
+		) -> Option<Quote>;
+	}
+}
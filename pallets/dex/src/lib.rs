@@ -5,6 +5,8 @@
 /// <https://docs.substrate.io/v3/runtime/frame>
 pub use pallet::*;
 
+pub mod migrations;
+
 #[cfg(test)]
 mod mock;
 
@@ -16,63 +18,526 @@ mod benchmarking;
 
 #[frame_support::pallet]
 pub mod pallet {
-	use frame_support::{dispatch::DispatchResult, ensure, pallet_prelude::*, transactional};
+	use frame_support::{
+		dispatch::DispatchResult,
+		ensure,
+		pallet_prelude::*,
+		traits::{Currency, ReservableCurrency},
+		transactional, PalletId,
+	};
 	use frame_system::pallet_prelude::*;
 	use sp_runtime::{
-		traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, IntegerSquareRoot, Zero},
-		Perbill,
+		traits::{
+			AccountIdConversion, CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Hash as HashT,
+			IntegerSquareRoot, One, SaturatedConversion, Saturating, TrailingZeroInput, Zero,
+		},
+		DispatchError, FixedPointNumber, FixedU128, PerThing, Perbill, RuntimeDebug,
 	};
+	use sp_std::vec::Vec;
 	use traits::Erc1155;
 
-	type BalanceOf<T> =
+	pub(crate) type BalanceOf<T> =
 		<<T as Config>::Tokens as Erc1155<<T as frame_system::Config>::AccountId>>::Balance;
 
-	type TokenIdOf<T> =
+	pub(crate) type TokenIdOf<T> =
 		<<T as Config>::Tokens as Erc1155<<T as frame_system::Config>::AccountId>>::TokenId;
 
+	pub(crate) type PoolInfoOf<T> =
+		PoolInfo<<T as frame_system::Config>::AccountId, TokenIdOf<T>, BalanceOf<T>>;
+
+	pub(crate) type RewardScheduleOf<T> =
+		RewardSchedule<TokenIdOf<T>, BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+
+	pub(crate) type SwapIntentOf<T> =
+		SwapIntent<<T as frame_system::Config>::AccountId, TokenIdOf<T>, BalanceOf<T>>;
+
+	pub(crate) type DepositBalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	pub(crate) type SwapCommitmentOf<T> =
+		SwapCommitment<<T as frame_system::Config>::BlockNumber, DepositBalanceOf<T>>;
+
+	/// Identifier of a liquidity pool.
+	pub type PoolId = u32;
+
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct PoolInfo<AccountId, TokenId, Balance> {
+		/// Account holding the reserves and the collected fees of the pool.
+		pub account: AccountId,
+		pub token_ids: (TokenId, TokenId),
+		pub total_liquidity: Balance,
+		/// Part of the input amount which is swapped, the rest of it is the fee.
+		pub fee: Perbill,
+	}
+
+	/// Liquidity mining emission: `reward_per_block` of `token_id` is shared pro rata between
+	/// stakers for every block in `[start, end)`.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct RewardSchedule<TokenId, Balance, BlockNumber> {
+		pub token_id: TokenId,
+		pub reward_per_block: Balance,
+		pub start: BlockNumber,
+		pub end: BlockNumber,
+	}
+
+	/// Staked liquidity of an account and the rewards accrued on it so far.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, Default)]
+	pub struct StakeInfo<Balance> {
+		pub amount: Balance,
+		/// Value of `RewardPerShare` at the moment `pending` was last updated.
+		pub reward_per_share_paid: FixedU128,
+		pub pending: Balance,
+	}
+
+	/// Swap fees owed to a liquidity provider, both tuples are ordered as `token_ids` of the pool.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, Default)]
+	pub struct FeePosition<Balance> {
+		/// Value of `FeeGrowth` at the moment `owed` was last updated.
+		pub fee_growth_paid: (FixedU128, FixedU128),
+		pub owed: (Balance, Balance),
+	}
+
+	/// A swap computed against the current reserves of a pool.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct SwapQuote<Balance> {
+		/// Amount of the input token which is swapped.
+		pub amount_in: Balance,
+		pub amount_out: Balance,
+		/// Part of `amount_in` taken as the fee.
+		pub fee: Balance,
+		/// Relative decrease of the marginal price of the input token caused by the swap.
+		pub price_impact: Perbill,
+	}
+
+	/// Swap of `amount` of `token_id` for the paired token of a pool in batch mode, settled at
+	/// the end of the block. The whole `amount` is either swapped or refunded.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct SwapIntent<AccountId, TokenId, Balance> {
+		pub who: AccountId,
+		pub token_id: TokenId,
+		pub amount: Balance,
+		/// The intent is refunded if it would buy less than this at the clearing price.
+		pub min_amount_out: Balance,
+	}
+
+	/// A swap committed to by its hash, which can be revealed in the blocks after `committed_at`.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct SwapCommitment<BlockNumber, Balance> {
+		pub committed_at: BlockNumber,
+		/// Reserved until the swap is revealed, forfeited if it is not revealed in time.
+		pub deposit: Balance,
+	}
+
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 		type Tokens: Erc1155<Self::AccountId>;
+		/// Fee of the pools created by `init`.
 		#[pallet::constant]
 		type Fee: Get<Perbill>;
+		/// Origin allowed to configure liquidity mining emissions.
+		type AdminOrigin: EnsureOrigin<Self::Origin>;
+		/// Used to derive the pool accounts and the account liquidity mining rewards are paid from.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+		/// Origin allowed to pause trading in an emergency.
+		type PauseOrigin: EnsureOrigin<Self::Origin>;
+		/// Maximum number of swap intents a pool in batch mode accepts in a block.
+		#[pallet::constant]
+		type MaxSwapIntents: Get<u32>;
+		/// Currency of the deposits of swap commitments.
+		type Currency: ReservableCurrency<Self::AccountId>;
+		/// Deposit reserved for a swap commitment.
+		#[pallet::constant]
+		type CommitmentDeposit: Get<DepositBalanceOf<Self>>;
+		/// Number of blocks after the commitment in which the swap can be revealed.
+		#[pallet::constant]
+		type RevealWindow: Get<Self::BlockNumber>;
+		/// Maximum number of swap commitments made in a block.
+		#[pallet::constant]
+		type MaxCommitmentsPerBlock: Get<u32>;
 	}
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::storage]
-	#[pallet::getter(fn get_pool_address)]
-	pub(super) type PoolAddress<T: Config> = StorageValue<_, T::AccountId>;
+	#[pallet::getter(fn get_next_pool_id)]
+	pub(super) type NextPoolId<T: Config> = StorageValue<_, PoolId, ValueQuery>;
 
 	#[pallet::storage]
-	#[pallet::getter(fn get_token_ids)]
-	pub(super) type TokenIds<T: Config> = StorageValue<_, (TokenIdOf<T>, TokenIdOf<T>)>;
+	#[pallet::getter(fn get_pool)]
+	pub(super) type Pools<T: Config> = StorageMap<_, Blake2_128Concat, PoolId, PoolInfoOf<T>>;
 
+	/// Pool of a token pair, stored for both orders of the tokens.
 	#[pallet::storage]
-	#[pallet::getter(fn get_total_liquidity)]
-	pub(super) type TotalLiquidity<T: Config> = StorageValue<_, BalanceOf<T>>;
+	#[pallet::getter(fn get_pool_id)]
+	pub(super) type PoolIds<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, TokenIdOf<T>, Blake2_128Concat, TokenIdOf<T>, PoolId>;
 
 	#[pallet::storage]
 	#[pallet::getter(fn get_liquidity)]
-	pub(super) type Liquidity<T: Config> =
-		StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+	pub(super) type Liquidity<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		PoolId,
+		Blake2_128Concat,
+		T::AccountId,
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	/// Swap fees collected per unit of liquidity, for each token of the pool.
+	#[pallet::storage]
+	#[pallet::getter(fn get_fee_growth)]
+	pub(super) type FeeGrowth<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		PoolId,
+		Blake2_128Concat,
+		TokenIdOf<T>,
+		FixedU128,
+		ValueQuery,
+	>;
+
+	/// Fees held by the pool account which are not a part of the reserves, as they are owed to
+	/// liquidity providers.
+	#[pallet::storage]
+	#[pallet::getter(fn get_collected_fees)]
+	pub(super) type CollectedFees<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		PoolId,
+		Blake2_128Concat,
+		TokenIdOf<T>,
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_fee_position)]
+	pub(super) type FeePositions<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		PoolId,
+		Blake2_128Concat,
+		T::AccountId,
+		FeePosition<BalanceOf<T>>,
+		ValueQuery,
+	>;
+
+	/// Swaps moving the price of a pool by more than this are rejected, there is no limit for
+	/// pools without an entry.
+	#[pallet::storage]
+	#[pallet::getter(fn get_max_price_impact)]
+	pub(super) type MaxPriceImpact<T: Config> = StorageMap<_, Blake2_128Concat, PoolId, Perbill>;
+
+	/// While a pool is paused its liquidity can only be withdrawn.
+	#[pallet::storage]
+	#[pallet::getter(fn is_paused)]
+	pub(super) type Paused<T: Config> = StorageMap<_, Blake2_128Concat, PoolId, bool, ValueQuery>;
+
+	/// Pools whose swaps are collected as intents and settled at the end of the block.
+	#[pallet::storage]
+	#[pallet::getter(fn is_batch_mode)]
+	pub(super) type BatchMode<T: Config> =
+		StorageMap<_, Blake2_128Concat, PoolId, bool, ValueQuery>;
+
+	/// Number of pools in batch mode, each of them may settle a batch in `on_finalize`.
+	#[pallet::storage]
+	#[pallet::getter(fn get_batch_pool_count)]
+	pub(super) type BatchPoolCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// Swap intents submitted in the current block.
+	#[pallet::storage]
+	#[pallet::getter(fn get_swap_intents)]
+	pub(super) type SwapIntents<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		PoolId,
+		BoundedVec<SwapIntentOf<T>, T::MaxSwapIntents>,
+		ValueQuery,
+	>;
+
+	/// Input of the pending swap intents and of the unclaimed refunds held by the pool account,
+	/// which is not a part of the reserves.
+	#[pallet::storage]
+	#[pallet::getter(fn get_batched_amount)]
+	pub(super) type BatchedAmounts<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		PoolId,
+		Blake2_128Concat,
+		TokenIdOf<T>,
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	/// Input of the swap intents whose refund failed, keyed by `(pool_id, who, token_id)`. It
+	/// can be paid out with `claim_refund`.
+	#[pallet::storage]
+	#[pallet::getter(fn get_unclaimed_refund)]
+	pub(super) type UnclaimedRefunds<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, PoolId>,
+			NMapKey<Blake2_128Concat, T::AccountId>,
+			NMapKey<Blake2_128Concat, TokenIdOf<T>>,
+		),
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_commitment)]
+	pub(super) type Commitments<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::Hash,
+		SwapCommitmentOf<T>,
+	>;
+
+	/// Commitments by the last block of their reveal window, the ones which are not revealed
+	/// by the end of it expire.
+	#[pallet::storage]
+	#[pallet::getter(fn get_commitment_expiries)]
+	pub(super) type CommitmentExpiries<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::BlockNumber,
+		BoundedVec<(T::AccountId, T::Hash), T::MaxCommitmentsPerBlock>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_reward_schedule)]
+	pub(super) type Schedule<T: Config> =
+		StorageMap<_, Blake2_128Concat, PoolId, RewardScheduleOf<T>>;
+
+	/// Accumulated reward per staked unit of liquidity.
+	#[pallet::storage]
+	#[pallet::getter(fn get_reward_per_share)]
+	pub(super) type RewardPerShare<T: Config> =
+		StorageMap<_, Blake2_128Concat, PoolId, FixedU128, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_last_reward_block)]
+	pub(super) type LastRewardBlock<T: Config> =
+		StorageMap<_, Blake2_128Concat, PoolId, T::BlockNumber, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_total_staked)]
+	pub(super) type TotalStaked<T: Config> =
+		StorageMap<_, Blake2_128Concat, PoolId, BalanceOf<T>, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_stake)]
+	pub(super) type Stakes<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		PoolId,
+		Blake2_128Concat,
+		T::AccountId,
+		StakeInfo<BalanceOf<T>>,
+		ValueQuery,
+	>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// Pools as `(owner, first_token_id, first_token_amount, second_token_id,
+		/// second_token_amount, fee)`, the reserves are moved from the owner, who gets all the
+		/// liquidity of the pool.
+		pub pools:
+			Vec<(T::AccountId, TokenIdOf<T>, BalanceOf<T>, TokenIdOf<T>, BalanceOf<T>, Perbill)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { pools: Vec::new() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			for (owner, first_token_id, first_amount, second_token_id, second_amount, fee) in
+				self.pools.iter().cloned()
+			{
+				let pool_id = Pallet::<T>::create_pool(
+					&owner,
+					first_token_id,
+					first_amount,
+					second_token_id,
+					second_amount,
+					fee,
+				)
+				.expect("genesis pools have to be valid");
+				let pool_account = Pallet::<T>::pool_account(pool_id);
+				for (token_id, amount) in
+					[(first_token_id, first_amount), (second_token_id, second_amount)]
+				{
+					T::Tokens::transfer(&owner, &pool_account, token_id, amount, &[])
+						.expect("owners of genesis pools have to hold the reserves");
+				}
+			}
+		}
+	}
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		Initialized(
-			T::AccountId,
-			T::AccountId,
-			TokenIdOf<T>,
-			BalanceOf<T>,
-			TokenIdOf<T>,
-			BalanceOf<T>,
-		),
-		TokenBought(T::AccountId, TokenIdOf<T>, BalanceOf<T>, TokenIdOf<T>, BalanceOf<T>),
-		Deposited(T::AccountId, TokenIdOf<T>, BalanceOf<T>, TokenIdOf<T>, BalanceOf<T>),
-		Withdrawed(T::AccountId, TokenIdOf<T>, BalanceOf<T>, TokenIdOf<T>, BalanceOf<T>),
+		/// A pool was created. Reserves and amounts in the events of a pool are ordered as its
+		/// `token_ids`, reserves are the ones after the operation.
+		Initialized {
+			who: T::AccountId,
+			pool_id: PoolId,
+			pool_account: T::AccountId,
+			token_ids: (TokenIdOf<T>, TokenIdOf<T>),
+			reserves: (BalanceOf<T>, BalanceOf<T>),
+			fee: Perbill,
+			liquidity_minted: BalanceOf<T>,
+		},
+		/// `fee_paid` is taken from `sold_amount`.
+		TokenBought {
+			who: T::AccountId,
+			pool_id: PoolId,
+			sold_token_id: TokenIdOf<T>,
+			sold_amount: BalanceOf<T>,
+			bought_token_id: TokenIdOf<T>,
+			bought_amount: BalanceOf<T>,
+			fee_paid: BalanceOf<T>,
+			reserves: (BalanceOf<T>, BalanceOf<T>),
+		},
+		Deposited {
+			who: T::AccountId,
+			pool_id: PoolId,
+			amounts: (BalanceOf<T>, BalanceOf<T>),
+			liquidity_minted: BalanceOf<T>,
+			reserves: (BalanceOf<T>, BalanceOf<T>),
+		},
+		/// `amount` of `token_id` was deposited, `swapped_amount` of it was swapped for the
+		/// paired token and `fee_paid` was taken from it.
+		SingleTokenDeposited {
+			who: T::AccountId,
+			pool_id: PoolId,
+			token_id: TokenIdOf<T>,
+			amount: BalanceOf<T>,
+			swapped_amount: BalanceOf<T>,
+			fee_paid: BalanceOf<T>,
+			liquidity_minted: BalanceOf<T>,
+			reserves: (BalanceOf<T>, BalanceOf<T>),
+		},
+		Withdrawed {
+			who: T::AccountId,
+			pool_id: PoolId,
+			amounts: (BalanceOf<T>, BalanceOf<T>),
+			liquidity_burned: BalanceOf<T>,
+			reserves: (BalanceOf<T>, BalanceOf<T>),
+		},
+		/// `amount` of `token_id` was withdrawn, `swapped_amount` of the paired token was swapped
+		/// for a part of it and `fee_paid` was taken from the paired token.
+		SingleTokenWithdrawn {
+			who: T::AccountId,
+			pool_id: PoolId,
+			token_id: TokenIdOf<T>,
+			amount: BalanceOf<T>,
+			swapped_amount: BalanceOf<T>,
+			fee_paid: BalanceOf<T>,
+			liquidity_burned: BalanceOf<T>,
+			reserves: (BalanceOf<T>, BalanceOf<T>),
+		},
+		RewardScheduleSet {
+			pool_id: PoolId,
+			token_id: TokenIdOf<T>,
+			reward_per_block: BalanceOf<T>,
+			start: T::BlockNumber,
+			end: T::BlockNumber,
+		},
+		Staked {
+			who: T::AccountId,
+			pool_id: PoolId,
+			amount: BalanceOf<T>,
+		},
+		Unstaked {
+			who: T::AccountId,
+			pool_id: PoolId,
+			amount: BalanceOf<T>,
+		},
+		RewardClaimed {
+			who: T::AccountId,
+			pool_id: PoolId,
+			token_id: TokenIdOf<T>,
+			amount: BalanceOf<T>,
+		},
+		FeesClaimed {
+			who: T::AccountId,
+			pool_id: PoolId,
+			fees: (BalanceOf<T>, BalanceOf<T>),
+		},
+		MaxPriceImpactSet {
+			pool_id: PoolId,
+			max_price_impact: Option<Perbill>,
+		},
+		BatchModeSet {
+			pool_id: PoolId,
+			enabled: bool,
+		},
+		SwapIntentSubmitted {
+			who: T::AccountId,
+			pool_id: PoolId,
+			token_id: TokenIdOf<T>,
+			amount: BalanceOf<T>,
+			min_amount_out: BalanceOf<T>,
+		},
+		/// The intent was not filled and its input was returned.
+		SwapIntentRefunded {
+			who: T::AccountId,
+			pool_id: PoolId,
+			token_id: TokenIdOf<T>,
+			amount: BalanceOf<T>,
+		},
+		/// The input of the intent could not be returned, it is kept for `claim_refund`.
+		SwapIntentRefundFailed {
+			who: T::AccountId,
+			pool_id: PoolId,
+			token_id: TokenIdOf<T>,
+			amount: BalanceOf<T>,
+		},
+		/// The filled intents of a batch were settled, each of them with a `TokenBought` event.
+		/// `clearing_price` is the price of the first token of the pool in the second one.
+		BatchSettled {
+			pool_id: PoolId,
+			clearing_price: FixedU128,
+			reserves: (BalanceOf<T>, BalanceOf<T>),
+		},
+		SwapCommitted {
+			who: T::AccountId,
+			commitment: T::Hash,
+		},
+		/// The committed swap was executed with a `TokenBought` event.
+		SwapRevealed {
+			who: T::AccountId,
+			commitment: T::Hash,
+		},
+		/// The commitment was not revealed in time and its deposit was forfeited.
+		CommitmentExpired {
+			who: T::AccountId,
+			commitment: T::Hash,
+			deposit: DepositBalanceOf<T>,
+		},
+		PoolPaused {
+			pool_id: PoolId,
+		},
+		PoolUnpaused {
+			pool_id: PoolId,
+		},
 	}
 
 	#[pallet::error]
@@ -85,47 +550,104 @@ pub mod pallet {
 		WrongShareValue,
 		NoLiquiudity,
 		NoLiquiudityToWithdraw,
+		WrongRewardSchedule,
+		NoRewardSchedule,
+		ZeroStakeAmount,
+		NotEnoughLiquidityToStake,
+		NotEnoughStaked,
+		LiquidityIsStaked,
+		NoRewardToClaim,
+		NoFeesToClaim,
+		Paused,
+		AlreadyPaused,
+		NotPaused,
+		PriceImpactTooHigh,
+		BatchModeEnabled,
+		NotInBatchMode,
+		TooManySwapIntents,
+		DuplicateCommitment,
+		UnknownCommitment,
+		RevealTooEarly,
+		TooManyCommitments,
+		SlippageExceeded,
+		RewardTokenInUse,
+		NoRefundToClaim,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Registers the weight of `on_finalize`, which settles up to `MaxSwapIntents` intents
+		/// of every pool in batch mode and expires the commitments of block `n`.
+		fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+			let db = T::DbWeight::get();
+			let intents = T::MaxSwapIntents::get() as Weight;
+			let batch = db
+				.reads_writes(6, 4)
+				.saturating_add(db.reads_writes(7, 9).saturating_mul(intents));
+			let expiring = CommitmentExpiries::<T>::decode_len(n).unwrap_or_default() as Weight;
+			db.reads(2)
+				.saturating_add(batch.saturating_mul(Self::get_batch_pool_count() as Weight))
+				.saturating_add(db.reads_writes(2, 2).saturating_mul(expiring))
+		}
+
+		fn on_finalize(n: BlockNumberFor<T>) {
+			for (pool_id, intents) in SwapIntents::<T>::drain() {
+				if Self::settle_batch(pool_id, intents.clone().into_inner()).is_err() {
+					Self::release_batched(pool_id, &intents);
+					Self::refund_intents(pool_id, intents.into_inner());
+				}
+			}
+			for (who, commitment) in CommitmentExpiries::<T>::take(n) {
+				if let Some(SwapCommitment { deposit, .. }) =
+					Commitments::<T>::take(&who, commitment)
+				{
+					let _ = T::Currency::slash_reserved(&who, deposit);
+					Self::deposit_event(Event::CommitmentExpired { who, commitment, deposit });
+				}
+			}
+		}
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
+		/// Create a pool for a new token pair, the sender provides the initial reserves and gets
+		/// all the liquidity of the pool.
 		#[pallet::weight(1000)]
 		#[transactional]
 		pub fn init(
 			origin: OriginFor<T>,
-			pool_address: T::AccountId,
 			first_token_id: TokenIdOf<T>,
 			first_token_amount: BalanceOf<T>,
 			second_token_id: TokenIdOf<T>,
 			second_token_amount: BalanceOf<T>,
 		) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
-			Self::uninitialized()?;
-			ensure!(
-				!first_token_amount.is_zero()
-					&& !second_token_amount.is_zero()
-					&& pool_address != T::AccountId::default(),
-				Error::<T>::WrongInitialization
-			);
-			T::Tokens::transfer_from_batch(
+			let pool_id = Self::create_pool(
 				&sender,
-				&pool_address,
-				&vec![first_token_id, second_token_id],
-				&vec![first_token_amount, second_token_amount],
-			)?;
-			let total_liquidity = first_token_amount.checked_add(&second_token_amount).unwrap();
-			TotalLiquidity::<T>::put(total_liquidity);
-			Liquidity::<T>::insert(&sender, total_liquidity);
-			TokenIds::<T>::put((first_token_id, second_token_id));
-			PoolAddress::<T>::put(&pool_address);
-			Self::deposit_event(Event::Initialized(
-				sender,
-				pool_address,
 				first_token_id,
 				first_token_amount,
 				second_token_id,
 				second_token_amount,
-			));
+				T::Fee::get(),
+			)?;
+			let pool_account = Self::pool_account(pool_id);
+			T::Tokens::transfer_from_batch(
+				&sender,
+				&pool_account,
+				&vec![first_token_id, second_token_id],
+				&vec![first_token_amount, second_token_amount],
+				&[],
+			)?;
+			let pool = Self::pool_info(pool_id)?;
+			Self::deposit_event(Event::Initialized {
+				who: sender,
+				pool_id,
+				pool_account,
+				token_ids: pool.token_ids,
+				reserves: (first_token_amount, second_token_amount),
+				fee: pool.fee,
+				liquidity_minted: pool.total_liquidity,
+			});
 			Ok(())
 		}
 
@@ -133,20 +655,12 @@ pub mod pallet {
 		#[transactional]
 		pub fn buy_token(
 			origin: OriginFor<T>,
+			pool_id: PoolId,
 			token_id: TokenIdOf<T>,
 			amount: BalanceOf<T>,
 		) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
-			Self::initialized()?;
-			Self::has_liquidity()?;
-			let token_to_buy = Self::get_paired_token(token_id).unwrap();
-			let pool = Self::get_pool_address().unwrap();
-			let reserves =
-				T::Tokens::balance_of_batch(vec![&pool, &pool], vec![token_id, token_to_buy])?;
-			let bought = Self::price(amount, reserves[0], reserves[1]).unwrap();
-			T::Tokens::transfer_from_single(&sender, &pool, &token_id, &amount)?;
-			T::Tokens::transfer(&pool, &sender, token_to_buy, bought)?;
-			Self::deposit_event(Event::TokenBought(sender, token_id, amount, token_to_buy, bought));
+			Self::do_buy_token(sender, pool_id, token_id, amount, true)?;
 			Ok(())
 		}
 
@@ -154,33 +668,38 @@ pub mod pallet {
 		#[transactional]
 		pub fn deposit(
 			origin: OriginFor<T>,
+			pool_id: PoolId,
 			token_id: TokenIdOf<T>,
 			amount: BalanceOf<T>,
 		) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
-			Self::initialized()?;
-			let paired_token = Self::get_paired_token(token_id).unwrap();
-			let pool = Self::get_pool_address().unwrap();
-			let reserves =
-				T::Tokens::balance_of_batch(vec![&pool, &pool], vec![token_id, paired_token])?;
-			let second_token_amount =
-				amount.checked_mul(&reserves[1]).unwrap().checked_div(&reserves[0]).unwrap();
-
-			Self::increase_liquidity(&sender, amount.checked_add(&second_token_amount).unwrap())?;
+			let pool = Self::pool_info(pool_id)?;
+			Self::not_paused(pool_id)?;
+			let paired_token = Self::get_paired_token(&pool, token_id)?;
+			let reserves = Self::get_reserves(pool_id, &pool, token_id, paired_token)?;
+			let second_token_amount = amount
+				.checked_mul(&reserves[1])
+				.and_then(|product| product.checked_div(&reserves[0]))
+				.ok_or(Error::<T>::Overflow)?;
+
+			let liquidity_minted =
+				amount.checked_add(&second_token_amount).ok_or(Error::<T>::Overflow)?;
+			Self::increase_liquidity(pool_id, &sender, liquidity_minted)?;
 			T::Tokens::transfer_from_batch(
 				&sender,
-				&pool,
+				&pool.account,
 				&vec![token_id, paired_token],
 				&vec![amount, second_token_amount],
+				&[],
 			)?;
 
-			Self::deposit_event(Event::Deposited(
-				sender,
-				token_id,
-				amount,
-				paired_token,
-				second_token_amount,
-			));
+			Self::deposit_event(Event::Deposited {
+				who: sender,
+				pool_id,
+				amounts: Self::in_pool_order(&pool, token_id, (amount, second_token_amount)),
+				liquidity_minted,
+				reserves: Self::pool_reserves(pool_id, &pool)?,
+			});
 			Ok(())
 		}
 
@@ -188,69 +707,73 @@ pub mod pallet {
 		#[transactional]
 		pub fn deposit_single_token(
 			origin: OriginFor<T>,
+			pool_id: PoolId,
 			token_id: TokenIdOf<T>,
 			amount: BalanceOf<T>,
 		) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
-			Self::initialized()?;
-			let paired_token = Self::get_paired_token(token_id).unwrap();
-			let pool = Self::get_pool_address().unwrap();
-			let reserves =
-				T::Tokens::balance_of_batch(vec![&pool, &pool], vec![token_id, paired_token])?;
+			let pool = Self::pool_info(pool_id)?;
+			Self::not_paused(pool_id)?;
+			Self::not_batched(pool_id)?;
+			let paired_token = Self::get_paired_token(&pool, token_id)?;
+			let reserves = Self::get_reserves(pool_id, &pool, token_id, paired_token)?;
 			let (token_to_swap, bought_paired_token) =
-				Self::calculate_single_token_ration(amount, reserves[0], reserves[1]).unwrap();
+				Self::calculate_single_token_ration(pool.fee, amount, reserves[0], reserves[1])
+					.ok_or(Error::<T>::Overflow)?;
+			let quote = Self::swap_quote(pool.fee, token_to_swap, reserves[0], reserves[1])?;
+			Self::ensure_price_impact(pool_id, &quote)?;
 
-			Self::increase_liquidity(
-				&sender,
-				token_to_swap.checked_add(&bought_paired_token).unwrap(),
-			)?;
-			T::Tokens::transfer_from_single(&sender, &pool, &token_id, &amount)?;
+			let fee_paid = Self::collect_fee(pool_id, token_id, token_to_swap)?;
+			let liquidity_minted =
+				token_to_swap.checked_add(&bought_paired_token).ok_or(Error::<T>::Overflow)?;
+			Self::increase_liquidity(pool_id, &sender, liquidity_minted)?;
+			T::Tokens::transfer_from_single(&sender, &pool.account, &token_id, &amount, &[])?;
 
-			Self::deposit_event(Event::TokenBought(
-				sender.clone(),
-				token_id,
-				token_to_swap,
-				paired_token,
-				bought_paired_token,
-			));
-			Self::deposit_event(Event::Deposited(
-				sender,
+			Self::deposit_event(Event::SingleTokenDeposited {
+				who: sender,
+				pool_id,
 				token_id,
-				amount.checked_sub(&token_to_swap).unwrap(),
-				paired_token,
-				bought_paired_token,
-			));
+				amount,
+				swapped_amount: token_to_swap,
+				fee_paid,
+				liquidity_minted,
+				reserves: Self::pool_reserves(pool_id, &pool)?,
+			});
 			Ok(())
 		}
 
 		#[pallet::weight(1000)]
 		#[transactional]
-		pub fn withdraw(origin: OriginFor<T>, share_percent: u32) -> DispatchResult {
+		pub fn withdraw(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			share_percent: u32,
+		) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
-			Self::initialized()?;
+			let pool = Self::pool_info(pool_id)?;
 			ensure!(share_percent > 0 && share_percent <= 100, Error::<T>::WrongShareValue);
 			let share_percent = Perbill::from_percent(share_percent);
 
-			let pool = Self::get_pool_address().unwrap();
-			let (token_1, token_2) = Self::get_token_ids().unwrap();
-			let reserves = T::Tokens::balance_of_batch(vec![&pool, &pool], vec![token_1, token_2])?;
-			let total_liquidity = Self::get_total_liquidity().unwrap();
+			let (token_1, token_2) = pool.token_ids;
+			let reserves = Self::get_reserves(pool_id, &pool, token_1, token_2)?;
 
-			let share_percent = share_percent
-				* Perbill::from_rational(Self::get_liquidity(&sender), total_liquidity);
+			let liquidity = Self::get_liquidity(pool_id, &sender);
+			let share_percent =
+				share_percent * Perbill::from_rational(liquidity, pool.total_liquidity);
 			let first_token_amount = share_percent * reserves[0];
 			let second_token_amount = share_percent * reserves[1];
 
-			Self::decrease_liquidity(&sender, share_percent * total_liquidity)?;
-			T::Tokens::transfer(&pool, &sender, token_1, first_token_amount)?;
-			T::Tokens::transfer(&pool, &sender, token_2, second_token_amount)?;
-			Self::deposit_event(Event::Withdrawed(
-				sender,
-				token_1,
-				first_token_amount,
-				token_2,
-				second_token_amount,
-			));
+			let liquidity_burned = share_percent * pool.total_liquidity;
+			Self::decrease_liquidity(pool_id, &sender, liquidity_burned)?;
+			T::Tokens::transfer(&pool.account, &sender, token_1, first_token_amount, &[])?;
+			T::Tokens::transfer(&pool.account, &sender, token_2, second_token_amount, &[])?;
+			Self::deposit_event(Event::Withdrawed {
+				who: sender,
+				pool_id,
+				amounts: (first_token_amount, second_token_amount),
+				liquidity_burned,
+				reserves: Self::pool_reserves(pool_id, &pool)?,
+			});
 			Ok(())
 		}
 
@@ -258,184 +781,1019 @@ pub mod pallet {
 		#[transactional]
 		pub fn withdraw_single_token(
 			origin: OriginFor<T>,
+			pool_id: PoolId,
 			token_id: TokenIdOf<T>,
 			share_percent: u32,
 		) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
-			Self::initialized()?;
+			let pool = Self::pool_info(pool_id)?;
+			// swaps a part of the share, so it is not available in the withdraw-only mode
+			Self::not_paused(pool_id)?;
+			Self::not_batched(pool_id)?;
 			ensure!(share_percent > 0 && share_percent <= 100, Error::<T>::WrongShareValue);
 			let share_percent =
-				Perbill::from_percent(share_percent) * Self::get_pool_share(&sender);
+				Perbill::from_percent(share_percent) * Self::get_pool_share(pool_id, &sender);
 			ensure!(share_percent != Perbill::from_percent(0), Error::<T>::NoLiquiudityToWithdraw);
 
-			let pool = Self::get_pool_address().unwrap();
-			let paired_token = Self::get_paired_token(token_id).unwrap();
-			let reserves =
-				T::Tokens::balance_of_batch(vec![&pool, &pool], vec![token_id, paired_token])?;
-			let total_liquidity = Self::get_total_liquidity().unwrap();
+			let paired_token = Self::get_paired_token(&pool, token_id)?;
+			let reserves = Self::get_reserves(pool_id, &pool, token_id, paired_token)?;
 
 			let first_token_amount = share_percent * reserves[0];
 			let second_token_amount = share_percent * reserves[1];
 
-			let bought_first_token = Self::price(
+			let quote = Self::swap_quote(
+				pool.fee,
 				second_token_amount,
 				reserves[1],
-				reserves[0].checked_sub(&first_token_amount).unwrap(),
-			)
-			.unwrap();
+				reserves[0].checked_sub(&first_token_amount).ok_or(Error::<T>::Overflow)?,
+			)?;
+			Self::ensure_price_impact(pool_id, &quote)?;
+			let bought_first_token = quote.amount_out;
 
-			Self::decrease_liquidity(&sender, share_percent * total_liquidity)?;
-			T::Tokens::transfer(
-				&pool,
-				&sender,
+			let liquidity_burned = share_percent * pool.total_liquidity;
+			Self::decrease_liquidity(pool_id, &sender, liquidity_burned)?;
+			let fee_paid = Self::collect_fee(pool_id, paired_token, second_token_amount)?;
+			let amount = first_token_amount
+				.checked_add(&bought_first_token)
+				.ok_or(Error::<T>::Overflow)?;
+			T::Tokens::transfer(&pool.account, &sender, token_id, amount, &[])?;
+			Self::deposit_event(Event::SingleTokenWithdrawn {
+				who: sender,
+				pool_id,
 				token_id,
-				first_token_amount.checked_add(&bought_first_token).unwrap(),
-			)?;
-			Self::deposit_event(Event::TokenBought(
-				sender.clone(),
-				paired_token,
-				second_token_amount,
+				amount,
+				swapped_amount: second_token_amount,
+				fee_paid,
+				liquidity_burned,
+				reserves: Self::pool_reserves(pool_id, &pool)?,
+			});
+
+			Ok(())
+		}
+
+		/// Pay out the swap fees accrued by the liquidity of the sender, the liquidity itself
+		/// stays in the pool.
+		#[pallet::weight(1000)]
+		#[transactional]
+		pub fn claim_fees(origin: OriginFor<T>, pool_id: PoolId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let pool = Self::pool_info(pool_id)?;
+			Self::accrue_fees(pool_id, &sender);
+			let (first_fee, second_fee) = FeePositions::<T>::mutate(pool_id, &sender, |position| {
+				sp_std::mem::take(&mut position.owed)
+			});
+			ensure!(!first_fee.is_zero() || !second_fee.is_zero(), Error::<T>::NoFeesToClaim);
+
+			let (token_1, token_2) = pool.token_ids;
+			for (token_id, fee) in [(token_1, first_fee), (token_2, second_fee)] {
+				if fee.is_zero() {
+					continue
+				}
+				CollectedFees::<T>::mutate(pool_id, token_id, |fees| {
+					*fees = fees.saturating_sub(fee)
+				});
+				T::Tokens::transfer(&pool.account, &sender, token_id, fee, &[])?;
+			}
+
+			Self::deposit_event(Event::FeesClaimed {
+				who: sender,
+				pool_id,
+				fees: (first_fee, second_fee),
+			});
+			Ok(())
+		}
+
+		#[pallet::weight(1000)]
+		pub fn pause_pool(origin: OriginFor<T>, pool_id: PoolId) -> DispatchResult {
+			T::PauseOrigin::ensure_origin(origin)?;
+			Self::pool_info(pool_id)?;
+			ensure!(!Self::is_paused(pool_id), Error::<T>::AlreadyPaused);
+			Paused::<T>::insert(pool_id, true);
+			Self::deposit_event(Event::PoolPaused { pool_id });
+			Ok(())
+		}
+
+		#[pallet::weight(1000)]
+		pub fn unpause_pool(origin: OriginFor<T>, pool_id: PoolId) -> DispatchResult {
+			T::PauseOrigin::ensure_origin(origin)?;
+			ensure!(Self::is_paused(pool_id), Error::<T>::NotPaused);
+			Paused::<T>::remove(pool_id);
+			Self::deposit_event(Event::PoolUnpaused { pool_id });
+			Ok(())
+		}
+
+		/// Limit the price impact of a single swap in the pool, `None` removes the limit.
+		#[pallet::weight(1000)]
+		pub fn set_max_price_impact(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			max_price_impact: Option<Perbill>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			Self::pool_info(pool_id)?;
+			MaxPriceImpact::<T>::set(pool_id, max_price_impact);
+			Self::deposit_event(Event::MaxPriceImpactSet { pool_id, max_price_impact });
+			Ok(())
+		}
+
+		/// Commit to a `buy_token` swap by `swap_commitment` of its parameters, which are revealed
+		/// by `reveal_swap` in one of the next `RevealWindow` blocks.
+		#[pallet::weight(1000)]
+		#[transactional]
+		pub fn commit_swap(origin: OriginFor<T>, commitment: T::Hash) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(
+				!Commitments::<T>::contains_key(&sender, commitment),
+				Error::<T>::DuplicateCommitment
+			);
+			let committed_at = frame_system::Pallet::<T>::block_number();
+			let expires_at = committed_at.saturating_add(T::RevealWindow::get());
+			CommitmentExpiries::<T>::try_mutate(expires_at, |expiries| {
+				expiries.try_push((sender.clone(), commitment))
+			})
+			.map_err(|_| Error::<T>::TooManyCommitments)?;
+			let deposit = T::CommitmentDeposit::get();
+			T::Currency::reserve(&sender, deposit)?;
+			Commitments::<T>::insert(&sender, commitment, SwapCommitment { committed_at, deposit });
+			Self::deposit_event(Event::SwapCommitted { who: sender, commitment });
+			Ok(())
+		}
+
+		/// Execute a committed swap, which fails if it buys less than `min_amount_out`.
+		#[pallet::weight(1000)]
+		#[transactional]
+		pub fn reveal_swap(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			token_id: TokenIdOf<T>,
+			amount: BalanceOf<T>,
+			min_amount_out: BalanceOf<T>,
+			salt: [u8; 32],
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let commitment =
+				Self::swap_commitment(&sender, pool_id, token_id, amount, min_amount_out, salt);
+			let SwapCommitment { committed_at, deposit } =
+				Commitments::<T>::take(&sender, commitment).ok_or(Error::<T>::UnknownCommitment)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() > committed_at,
+				Error::<T>::RevealTooEarly
+			);
+			CommitmentExpiries::<T>::mutate(
+				committed_at.saturating_add(T::RevealWindow::get()),
+				|expiries| expiries.retain(|expiry| *expiry != (sender.clone(), commitment)),
+			);
+			T::Currency::unreserve(&sender, deposit);
+			let bought = Self::do_buy_token(sender.clone(), pool_id, token_id, amount, true)?;
+			ensure!(bought >= min_amount_out, Error::<T>::SlippageExceeded);
+			Self::deposit_event(Event::SwapRevealed { who: sender, commitment });
+			Ok(())
+		}
+
+		/// In batch mode the swaps of the pool only go through `submit_swap_intent`.
+		#[pallet::weight(1000)]
+		pub fn set_batch_mode(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			enabled: bool,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			Self::pool_info(pool_id)?;
+			if Self::is_batch_mode(pool_id) != enabled {
+				BatchPoolCount::<T>::mutate(|count| {
+					*count = if enabled { count.saturating_add(1) } else { count.saturating_sub(1) }
+				});
+			}
+			BatchMode::<T>::insert(pool_id, enabled);
+			Self::deposit_event(Event::BatchModeSet { pool_id, enabled });
+			Ok(())
+		}
+
+		/// Sell `amount` of `token_id` at the clearing price of the batch of the current block.
+		/// The tokens are moved to the pool account until the batch is settled.
+		#[pallet::weight(1000)]
+		#[transactional]
+		pub fn submit_swap_intent(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			token_id: TokenIdOf<T>,
+			amount: BalanceOf<T>,
+			min_amount_out: BalanceOf<T>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let pool = Self::pool_info(pool_id)?;
+			Self::not_paused(pool_id)?;
+			ensure!(Self::is_batch_mode(pool_id), Error::<T>::NotInBatchMode);
+			Self::has_liquidity(&pool)?;
+			Self::get_paired_token(&pool, token_id)?;
+			let intent = SwapIntent { who: sender.clone(), token_id, amount, min_amount_out };
+			SwapIntents::<T>::try_mutate(pool_id, |intents| intents.try_push(intent))
+				.map_err(|_| Error::<T>::TooManySwapIntents)?;
+			let batched = Self::get_batched_amount(pool_id, token_id)
+				.checked_add(&amount)
+				.ok_or(Error::<T>::Overflow)?;
+			BatchedAmounts::<T>::insert(pool_id, token_id, batched);
+			T::Tokens::transfer_from_single(&sender, &pool.account, &token_id, &amount, &[])?;
+			Self::deposit_event(Event::SwapIntentSubmitted {
+				who: sender,
+				pool_id,
 				token_id,
-				bought_first_token,
-			));
-			Self::deposit_event(Event::Withdrawed(
-				sender,
+				amount,
+				min_amount_out,
+			});
+			Ok(())
+		}
+
+		/// Pay out the input of the swap intents of the sender whose refund failed.
+		#[pallet::weight(1000)]
+		#[transactional]
+		pub fn claim_refund(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			token_id: TokenIdOf<T>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let pool = Self::pool_info(pool_id)?;
+			let amount = UnclaimedRefunds::<T>::take((pool_id, &sender, token_id));
+			ensure!(!amount.is_zero(), Error::<T>::NoRefundToClaim);
+			BatchedAmounts::<T>::mutate(pool_id, token_id, |batched| {
+				*batched = batched.saturating_sub(amount)
+			});
+			T::Tokens::transfer(&pool.account, &sender, token_id, amount, &[])?;
+			Self::deposit_event(Event::SwapIntentRefunded {
+				who: sender,
+				pool_id,
 				token_id,
-				first_token_amount,
-				paired_token,
-				BalanceOf::<T>::default(),
-			));
+				amount,
+			});
+			Ok(())
+		}
 
+		#[pallet::weight(1000)]
+		#[transactional]
+		pub fn set_reward_schedule(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			token_id: TokenIdOf<T>,
+			reward_per_block: BalanceOf<T>,
+			start: T::BlockNumber,
+			end: T::BlockNumber,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			Self::pool_info(pool_id)?;
+			ensure!(start < end && !reward_per_block.is_zero(), Error::<T>::WrongRewardSchedule);
+			// pending rewards are paid in the token of the current schedule, including the ones
+			// of fully unstaked liquidity which are not claimed yet
+			if let Some(schedule) = Self::get_reward_schedule(pool_id) {
+				ensure!(
+					schedule.token_id == token_id ||
+						(Self::get_total_staked(pool_id).is_zero() &&
+							Stakes::<T>::iter_prefix_values(pool_id)
+								.all(|stake| stake.pending.is_zero())),
+					Error::<T>::RewardTokenInUse
+				);
+			}
+			// rewards emitted so far are accrued with the previous schedule
+			Self::update_reward_per_share(pool_id);
+			Schedule::<T>::insert(
+				pool_id,
+				RewardSchedule { token_id, reward_per_block, start, end },
+			);
+			Self::deposit_event(Event::RewardScheduleSet {
+				pool_id,
+				token_id,
+				reward_per_block,
+				start,
+				end,
+			});
+			Ok(())
+		}
+
+		#[pallet::weight(1000)]
+		#[transactional]
+		pub fn stake(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::pool_info(pool_id)?;
+			ensure!(!amount.is_zero(), Error::<T>::ZeroStakeAmount);
+			let staked = Self::get_stake(pool_id, &sender).amount;
+			ensure!(
+				Self::get_liquidity(pool_id, &sender).checked_sub(&staked).unwrap_or_default() >=
+					amount,
+				Error::<T>::NotEnoughLiquidityToStake
+			);
+
+			Self::update_reward_per_share(pool_id);
+			Stakes::<T>::try_mutate(pool_id, &sender, |stake| -> DispatchResult {
+				Self::accrue_reward(pool_id, stake);
+				stake.amount = stake.amount.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+				Ok(())
+			})?;
+			TotalStaked::<T>::try_mutate(pool_id, |total| -> DispatchResult {
+				*total = total.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::Staked { who: sender, pool_id, amount });
+			Ok(())
+		}
+
+		#[pallet::weight(1000)]
+		#[transactional]
+		pub fn unstake(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(!amount.is_zero(), Error::<T>::ZeroStakeAmount);
+
+			Self::update_reward_per_share(pool_id);
+			Stakes::<T>::try_mutate(pool_id, &sender, |stake| -> DispatchResult {
+				Self::accrue_reward(pool_id, stake);
+				stake.amount =
+					stake.amount.checked_sub(&amount).ok_or(Error::<T>::NotEnoughStaked)?;
+				Ok(())
+			})?;
+			TotalStaked::<T>::try_mutate(pool_id, |total| -> DispatchResult {
+				*total = total.checked_sub(&amount).ok_or(Error::<T>::Overflow)?;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::Unstaked { who: sender, pool_id, amount });
+			Ok(())
+		}
+
+		#[pallet::weight(1000)]
+		#[transactional]
+		pub fn claim(origin: OriginFor<T>, pool_id: PoolId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let schedule =
+				Self::get_reward_schedule(pool_id).ok_or(Error::<T>::NoRewardSchedule)?;
+
+			Self::update_reward_per_share(pool_id);
+			let reward = Stakes::<T>::mutate(pool_id, &sender, |stake| {
+				Self::accrue_reward(pool_id, stake);
+				sp_std::mem::take(&mut stake.pending)
+			});
+			ensure!(!reward.is_zero(), Error::<T>::NoRewardToClaim);
+			T::Tokens::transfer(&Self::reward_account(), &sender, schedule.token_id, reward, &[])?;
+
+			Self::deposit_event(Event::RewardClaimed {
+				who: sender,
+				pool_id,
+				token_id: schedule.token_id,
+				amount: reward,
+			});
 			Ok(())
 		}
 	}
 
 	impl<T: Config> Pallet<T> {
 		fn price(
+			fee: Perbill,
 			input_amount: BalanceOf<T>,
 			input_reserve: BalanceOf<T>,
 			output_reserve: BalanceOf<T>,
 		) -> Option<BalanceOf<T>> {
-			let input_amount_with_fee = T::Fee::get() * input_amount;
+			let input_amount_with_fee = fee * input_amount;
 			input_amount_with_fee
-				.checked_mul(&output_reserve)
-				.unwrap()
-				.checked_div(&input_reserve.checked_add(&input_amount_with_fee).unwrap())
+				.checked_mul(&output_reserve)?
+				.checked_div(&input_reserve.checked_add(&input_amount_with_fee)?)
+		}
+
+		/// Swap `input_amount` against the reserves used by `price`. The fee stays out of the
+		/// reserves, so only the swapped part moves the price: for constant product reserves the
+		/// price of the input token drops by `1 - (input_reserve / (input_reserve + swapped))^2`.
+		fn swap_quote(
+			fee: Perbill,
+			input_amount: BalanceOf<T>,
+			input_reserve: BalanceOf<T>,
+			output_reserve: BalanceOf<T>,
+		) -> Result<SwapQuote<BalanceOf<T>>, Error<T>> {
+			let swapped = fee * input_amount;
+			let reserve_ratio =
+				Perbill::from_rational(input_reserve, input_reserve.saturating_add(swapped));
+			Ok(SwapQuote {
+				amount_in: input_amount,
+				amount_out: Self::price(fee, input_amount, input_reserve, output_reserve)
+					.ok_or(Error::<T>::Overflow)?,
+				fee: input_amount.saturating_sub(swapped),
+				price_impact: Perbill::from_percent(100)
+					.saturating_sub(reserve_ratio * reserve_ratio),
+			})
+		}
+
+		/// Sell `amount` of `token_id` to the pool, returns the bought amount of the paired token.
+		/// The input is moved with `transfer_from_single` if `approved` is set, which requires the
+		/// pool to be approved by `sender`.
+		fn do_buy_token(
+			sender: T::AccountId,
+			pool_id: PoolId,
+			token_id: TokenIdOf<T>,
+			amount: BalanceOf<T>,
+			approved: bool,
+		) -> Result<BalanceOf<T>, DispatchError> {
+			let pool = Self::pool_info(pool_id)?;
+			Self::not_paused(pool_id)?;
+			Self::not_batched(pool_id)?;
+			Self::has_liquidity(&pool)?;
+			let token_to_buy = Self::get_paired_token(&pool, token_id)?;
+			let reserves = Self::get_reserves(pool_id, &pool, token_id, token_to_buy)?;
+			let quote = Self::swap_quote(pool.fee, amount, reserves[0], reserves[1])?;
+			Self::ensure_price_impact(pool_id, &quote)?;
+			let bought = quote.amount_out;
+			if approved {
+				T::Tokens::transfer_from_single(&sender, &pool.account, &token_id, &amount, &[])?;
+			} else {
+				T::Tokens::transfer(&sender, &pool.account, token_id, amount, &[])?;
+			}
+			T::Tokens::transfer(&pool.account, &sender, token_to_buy, bought, &[])?;
+			let fee_paid = Self::collect_fee(pool_id, token_id, amount)?;
+			Self::deposit_event(Event::TokenBought {
+				who: sender,
+				pool_id,
+				sold_token_id: token_id,
+				sold_amount: amount,
+				bought_token_id: token_to_buy,
+				bought_amount: bought,
+				fee_paid,
+				reserves: Self::pool_reserves(pool_id, &pool)?,
+			});
+			Ok(bought)
+		}
+
+		/// Sell `amount` of `token_id` to the pool like `buy_token` does, returns the bought amount.
+		/// No approval of the pool is required, so the caller has to make sure `who` authorized
+		/// the swap, e.g. by signing the transaction it is made for.
+		#[transactional]
+		pub fn swap_exact_in(
+			who: &T::AccountId,
+			pool_id: PoolId,
+			token_id: TokenIdOf<T>,
+			amount: BalanceOf<T>,
+		) -> Result<BalanceOf<T>, DispatchError> {
+			Self::do_buy_token(who.clone(), pool_id, token_id, amount, false)
+		}
+
+		/// Buy at least `amount_out` of the token paired with `token_id` for at most
+		/// `max_amount_in` of `token_id`, returns the sold amount. Requires the same authorization
+		/// of `who` as `swap_exact_in`.
+		#[transactional]
+		pub fn swap_exact_out(
+			who: &T::AccountId,
+			pool_id: PoolId,
+			token_id: TokenIdOf<T>,
+			amount_out: BalanceOf<T>,
+			max_amount_in: BalanceOf<T>,
+		) -> Result<BalanceOf<T>, DispatchError> {
+			let amount_in = Self::quote_exact_out(pool_id, token_id, amount_out)?;
+			ensure!(amount_in <= max_amount_in, Error::<T>::SlippageExceeded);
+			let bought = Self::do_buy_token(who.clone(), pool_id, token_id, amount_in, false)?;
+			ensure!(bought >= amount_out, Error::<T>::SlippageExceeded);
+			Ok(amount_in)
+		}
+
+		/// Amount of `token_id` which has to be sold to buy at least `amount_out` of the paired
+		/// token.
+		pub fn quote_exact_out(
+			pool_id: PoolId,
+			token_id: TokenIdOf<T>,
+			amount_out: BalanceOf<T>,
+		) -> Result<BalanceOf<T>, DispatchError> {
+			let pool = Self::pool_info(pool_id)?;
+			Self::has_liquidity(&pool)?;
+			let token_to_buy = Self::get_paired_token(&pool, token_id)?;
+			let reserves = Self::get_reserves(pool_id, &pool, token_id, token_to_buy)?;
+			ensure!(amount_out < reserves[1], Error::<T>::NoLiquiudity);
+			// inverse of `price`, rounded up
+			let swapped = amount_out
+				.checked_mul(&reserves[0])
+				.ok_or(Error::<T>::Overflow)?
+				.saturating_add(reserves[1] - amount_out - One::one())
+				/ (reserves[1] - amount_out);
+			let amount_in = pool.fee.saturating_reciprocal_mul_ceil(swapped);
+			// `Perbill` multiplication rounds to the nearest integer
+			let quote = Self::swap_quote(pool.fee, amount_in, reserves[0], reserves[1])?;
+			if quote.amount_out < amount_out {
+				return Ok(amount_in.saturating_add(One::one()))
+			}
+			Ok(amount_in)
+		}
+
+		/// Hash committed to by `commit_swap` for a swap of `who`.
+		pub fn swap_commitment(
+			who: &T::AccountId,
+			pool_id: PoolId,
+			token_id: TokenIdOf<T>,
+			amount: BalanceOf<T>,
+			min_amount_out: BalanceOf<T>,
+			salt: [u8; 32],
+		) -> T::Hash {
+			T::Hashing::hash_of(&(who, pool_id, token_id, amount, min_amount_out, salt))
+		}
+
+		/// Settle `intents` at the uniform clearing price `(reserve_1 + sold_1) / (reserve_0 +
+		/// sold_0)` of the first token in the second one, where `sold_*` are the swapped parts of
+		/// the intents selling each token. At this price the net flow between the two sides keeps
+		/// the product of the reserves. Intents are filled all or nothing: the ones whose limit is
+		/// not met are refunded in full and the price is computed again without them.
+		///
+		/// The reserves are read before the batched inputs are released into them.
+		#[transactional]
+		fn settle_batch(pool_id: PoolId, mut intents: Vec<SwapIntentOf<T>>) -> DispatchResult {
+			let pool = Self::pool_info(pool_id)?;
+			Self::not_paused(pool_id)?;
+			Self::has_liquidity(&pool)?;
+			let reserves = Self::pool_reserves(pool_id, &pool)?;
+			Self::release_batched(pool_id, &intents);
+
+			let mut refunded = Vec::new();
+			let (clearing_price, fills) = loop {
+				let (sold_0, sold_1) = intents.iter().fold(
+					(BalanceOf::<T>::zero(), BalanceOf::<T>::zero()),
+					|(sold_0, sold_1), intent| {
+						let sold = pool.fee * intent.amount;
+						if intent.token_id == pool.token_ids.0 {
+							(sold_0.saturating_add(sold), sold_1)
+						} else {
+							(sold_0, sold_1.saturating_add(sold))
+						}
+					},
+				);
+				let (total_0, total_1) = (
+					reserves.0.saturating_add(sold_0).saturated_into::<u128>(),
+					reserves.1.saturating_add(sold_1).saturated_into::<u128>(),
+				);
+				let price_0 = FixedU128::saturating_from_rational(total_1, total_0);
+				let price_1 = FixedU128::saturating_from_rational(total_0, total_1);
+				let (met, unmet): (Vec<_>, Vec<_>) = intents
+					.into_iter()
+					.map(|intent| {
+						let price =
+							if intent.token_id == pool.token_ids.0 { price_0 } else { price_1 };
+						let sold = (pool.fee * intent.amount).saturated_into::<u128>();
+						let amount_out = price.saturating_mul_int(sold).saturated_into();
+						(intent, amount_out)
+					})
+					.partition(|(intent, amount_out)| *amount_out >= intent.min_amount_out);
+				if unmet.is_empty() {
+					break (price_0, met)
+				}
+				refunded.extend(unmet.into_iter().map(|(intent, _)| intent));
+				intents = met.into_iter().map(|(intent, _)| intent).collect();
+			};
+
+			let mut bought = Vec::new();
+			for (intent, amount_out) in fills {
+				let token_to_buy = Self::get_paired_token(&pool, intent.token_id)?;
+				T::Tokens::transfer(&pool.account, &intent.who, token_to_buy, amount_out, &[])?;
+				let fee_paid = Self::collect_fee(pool_id, intent.token_id, intent.amount)?;
+				bought.push((intent, token_to_buy, amount_out, fee_paid));
+			}
+			Self::refund_intents(pool_id, refunded);
+
+			let reserves = Self::pool_reserves(pool_id, &pool)?;
+			for (intent, token_to_buy, amount_out, fee_paid) in bought {
+				Self::deposit_event(Event::TokenBought {
+					who: intent.who,
+					pool_id,
+					sold_token_id: intent.token_id,
+					sold_amount: intent.amount,
+					bought_token_id: token_to_buy,
+					bought_amount: amount_out,
+					fee_paid,
+					reserves,
+				});
+			}
+			Self::deposit_event(Event::BatchSettled { pool_id, clearing_price, reserves });
+			Ok(())
+		}
+
+		/// Move the input of `intents` out of `BatchedAmounts`, so that it becomes a part of the
+		/// reserves.
+		fn release_batched(pool_id: PoolId, intents: &[SwapIntentOf<T>]) {
+			for intent in intents {
+				BatchedAmounts::<T>::mutate(pool_id, intent.token_id, |batched| {
+					*batched = batched.saturating_sub(intent.amount)
+				});
+			}
+		}
+
+		/// Return the input of `intents`, the input of a failed refund stays out of the reserves
+		/// until it is paid out with `claim_refund`.
+		fn refund_intents(pool_id: PoolId, intents: Vec<SwapIntentOf<T>>) {
+			let pool = match Self::get_pool(pool_id) {
+				Some(pool) => pool,
+				None => return,
+			};
+			for intent in intents {
+				let SwapIntent { who, token_id, amount, .. } = intent;
+				if T::Tokens::transfer(&pool.account, &who, token_id, amount, &[]).is_ok() {
+					Self::deposit_event(Event::SwapIntentRefunded {
+						who,
+						pool_id,
+						token_id,
+						amount,
+					});
+				} else {
+					BatchedAmounts::<T>::mutate(pool_id, token_id, |batched| {
+						*batched = batched.saturating_add(amount)
+					});
+					UnclaimedRefunds::<T>::mutate((pool_id, &who, token_id), |unclaimed| {
+						*unclaimed = unclaimed.saturating_add(amount)
+					});
+					Self::deposit_event(Event::SwapIntentRefundFailed {
+						who,
+						pool_id,
+						token_id,
+						amount,
+					});
+				}
+			}
+		}
+
+		fn ensure_price_impact(
+			pool_id: PoolId,
+			quote: &SwapQuote<BalanceOf<T>>,
+		) -> Result<(), Error<T>> {
+			if let Some(max_price_impact) = Self::get_max_price_impact(pool_id) {
+				ensure!(quote.price_impact <= max_price_impact, Error::<T>::PriceImpactTooHigh);
+			}
+			Ok(())
+		}
+
+		/// Quote of `buy_token`.
+		pub fn quote_buy(
+			pool_id: PoolId,
+			token_id: TokenIdOf<T>,
+			amount: BalanceOf<T>,
+		) -> Result<SwapQuote<BalanceOf<T>>, DispatchError> {
+			let pool = Self::pool_info(pool_id)?;
+			Self::has_liquidity(&pool)?;
+			let token_to_buy = Self::get_paired_token(&pool, token_id)?;
+			let reserves = Self::get_reserves(pool_id, &pool, token_id, token_to_buy)?;
+			Ok(Self::swap_quote(pool.fee, amount, reserves[0], reserves[1])?)
+		}
+
+		/// Quote of the swap `deposit_single_token` makes, `amount_in` is the part of `amount`
+		/// which is swapped for the paired token.
+		pub fn quote_deposit_single_token(
+			pool_id: PoolId,
+			token_id: TokenIdOf<T>,
+			amount: BalanceOf<T>,
+		) -> Result<SwapQuote<BalanceOf<T>>, DispatchError> {
+			let pool = Self::pool_info(pool_id)?;
+			Self::has_liquidity(&pool)?;
+			let paired_token = Self::get_paired_token(&pool, token_id)?;
+			let reserves = Self::get_reserves(pool_id, &pool, token_id, paired_token)?;
+			let (token_to_swap, _) =
+				Self::calculate_single_token_ration(pool.fee, amount, reserves[0], reserves[1])
+					.ok_or(Error::<T>::Overflow)?;
+			Ok(Self::swap_quote(pool.fee, token_to_swap, reserves[0], reserves[1])?)
+		}
+
+		/// Quote of the swap `withdraw_single_token` makes for `owner`, `amount_in` is the part
+		/// of the share in the paired token which is swapped for `token_id`.
+		pub fn quote_withdraw_single_token(
+			pool_id: PoolId,
+			owner: &T::AccountId,
+			token_id: TokenIdOf<T>,
+			share_percent: u32,
+		) -> Result<SwapQuote<BalanceOf<T>>, DispatchError> {
+			let pool = Self::pool_info(pool_id)?;
+			ensure!(share_percent > 0 && share_percent <= 100, Error::<T>::WrongShareValue);
+			let share_percent =
+				Perbill::from_percent(share_percent) * Self::get_pool_share(pool_id, owner);
+			let paired_token = Self::get_paired_token(&pool, token_id)?;
+			let reserves = Self::get_reserves(pool_id, &pool, token_id, paired_token)?;
+			Ok(Self::swap_quote(
+				pool.fee,
+				share_percent * reserves[1],
+				reserves[1],
+				reserves[0].saturating_sub(share_percent * reserves[0]),
+			)?)
 		}
 
 		/// Calculate the amount of input token we need to swap for second token to achieve correct ratio
 		/// considering fee and the fact that token ration changed after we did token swap
 		fn calculate_single_token_ration(
+			fee: Perbill,
 			input_amount: BalanceOf<T>,
 			input_reserve: BalanceOf<T>,
 			output_reserve: BalanceOf<T>,
 		) -> Option<(BalanceOf<T>, BalanceOf<T>)> {
-			let fee = T::Fee::get();
-			let two: BalanceOf<T> = 2u32.try_into().ok().unwrap();
-			let discriminant_sqrt = input_reserve
-				.integer_sqrt_checked()
-				.unwrap()
-				.checked_mul(
-					&((fee * fee * input_reserve)
-						.checked_add(&(fee * input_reserve.checked_mul(&two).unwrap()))
-						.unwrap()
-						.checked_add(&(fee * two * two.checked_mul(&input_amount).unwrap()))
-						.unwrap()
-						.checked_add(&input_reserve)
-						.unwrap()
-						.integer_sqrt_checked()
-						.unwrap()),
-				)
-				.unwrap();
+			let two: BalanceOf<T> = 2u32.into();
+			let discriminant_sqrt = input_reserve.integer_sqrt_checked()?.checked_mul(
+				&(fee * fee * input_reserve)
+					.checked_add(&(fee * input_reserve.checked_mul(&two)?))?
+					.checked_add(&(fee * two * two.checked_mul(&input_amount)?))?
+					.checked_add(&input_reserve)?
+					.integer_sqrt_checked()?,
+			)?;
 			let tokens_to_swap = discriminant_sqrt
-				.checked_sub(&input_reserve)
-				.unwrap()
-				.checked_sub(&(fee * input_reserve))
-				.unwrap()
-				.checked_div(&(fee * two))
-				.unwrap();
-
-			let bought = Self::price(tokens_to_swap, input_reserve, output_reserve).unwrap();
+				.checked_sub(&input_reserve)?
+				.checked_sub(&(fee * input_reserve))?
+				.checked_div(&(fee * two))?;
+
+			let bought = Self::price(fee, tokens_to_swap, input_reserve, output_reserve)?;
 			Some((tokens_to_swap, bought))
 		}
 
-		fn get_paired_token(token_id: TokenIdOf<T>) -> Option<TokenIdOf<T>> {
-			let (token_1, token_2) = Self::get_token_ids().unwrap();
+		/// Register a new pool giving `owner` all of its liquidity, the caller has to move the
+		/// initial reserves to the pool account.
+		fn create_pool(
+			owner: &T::AccountId,
+			first_token_id: TokenIdOf<T>,
+			first_token_amount: BalanceOf<T>,
+			second_token_id: TokenIdOf<T>,
+			second_token_amount: BalanceOf<T>,
+			fee: Perbill,
+		) -> Result<PoolId, DispatchError> {
+			ensure!(
+				!first_token_amount.is_zero() &&
+					!second_token_amount.is_zero() &&
+					first_token_id != second_token_id,
+				Error::<T>::WrongInitialization
+			);
+			ensure!(
+				!PoolIds::<T>::contains_key(first_token_id, second_token_id),
+				Error::<T>::AlreadyInitialized
+			);
+			let pool_id = Self::get_next_pool_id();
+			let account = Self::pool_account(pool_id);
+			let total_liquidity = first_token_amount
+				.checked_add(&second_token_amount)
+				.ok_or(Error::<T>::Overflow)?;
+			Liquidity::<T>::insert(pool_id, owner, total_liquidity);
+			Pools::<T>::insert(
+				pool_id,
+				PoolInfo {
+					account,
+					token_ids: (first_token_id, second_token_id),
+					total_liquidity,
+					fee,
+				},
+			);
+			PoolIds::<T>::insert(first_token_id, second_token_id, pool_id);
+			PoolIds::<T>::insert(second_token_id, first_token_id, pool_id);
+			NextPoolId::<T>::put(pool_id.checked_add(1).ok_or(Error::<T>::Overflow)?);
+			Ok(pool_id)
+		}
+
+		/// Account holding the reserves of a pool, it is known before the pool is created, so
+		/// transfers to it can be approved in advance.
+		pub fn pool_account(pool_id: PoolId) -> T::AccountId {
+			let entropy = T::Hashing::hash_of(&(T::PalletId::get(), b"pool", pool_id));
+			T::AccountId::decode(&mut TrailingZeroInput::new(entropy.as_ref())).unwrap_or_default()
+		}
+
+		fn pool_info(pool_id: PoolId) -> Result<PoolInfoOf<T>, Error<T>> {
+			Self::get_pool(pool_id).ok_or(Error::<T>::Uninitilized)
+		}
+
+		fn get_paired_token(
+			pool: &PoolInfoOf<T>,
+			token_id: TokenIdOf<T>,
+		) -> Result<TokenIdOf<T>, Error<T>> {
+			let (token_1, token_2) = pool.token_ids;
 			match token_id {
-				t1 if t1 == token_1 => Some(token_2),
-				t2 if t2 == token_2 => Some(token_1),
-				_ => None,
+				t1 if t1 == token_1 => Ok(token_2),
+				t2 if t2 == token_2 => Ok(token_1),
+				_ => Err(Error::<T>::WrongTokenId),
 			}
 		}
 
-		pub fn get_pool_share(owner: &T::AccountId) -> Perbill {
-			Perbill::from_rational(
-				Self::get_liquidity(&owner),
-				Self::get_total_liquidity().unwrap(),
-			)
+		pub fn get_pool_share(pool_id: PoolId, owner: &T::AccountId) -> Perbill {
+			let total_liquidity =
+				Self::get_pool(pool_id).map(|pool| pool.total_liquidity).unwrap_or_default();
+			Perbill::from_rational(Self::get_liquidity(pool_id, owner), total_liquidity)
+		}
+
+		/// Swap fees `owner` could claim right now, ordered as `token_ids` of the pool.
+		pub fn get_pending_fees(
+			pool_id: PoolId,
+			owner: &T::AccountId,
+		) -> (BalanceOf<T>, BalanceOf<T>) {
+			let mut position = Self::get_fee_position(pool_id, owner);
+			Self::update_fee_position(pool_id, &mut position, Self::get_liquidity(pool_id, owner));
+			position.owed
 		}
 
-		pub fn get_reward(owner: &T::AccountId) -> BalanceOf<T> {
-			Self::get_pool_share(owner) * Self::get_total_reward().unwrap()
+		/// Pool balances without the fees owed to liquidity providers.
+		fn get_reserves(
+			pool_id: PoolId,
+			pool: &PoolInfoOf<T>,
+			first_token: TokenIdOf<T>,
+			second_token: TokenIdOf<T>,
+		) -> Result<[BalanceOf<T>; 2], DispatchError> {
+			let balances = T::Tokens::balance_of_batch(
+				vec![&pool.account, &pool.account],
+				vec![first_token, second_token],
+			)?;
+			let reserve = |balance: BalanceOf<T>, token_id| {
+				balance
+					.saturating_sub(Self::get_collected_fees(pool_id, token_id))
+					.saturating_sub(Self::get_batched_amount(pool_id, token_id))
+			};
+			Ok([reserve(balances[0], first_token), reserve(balances[1], second_token)])
 		}
 
-		pub fn get_total_reward() -> Result<BalanceOf<T>, sp_runtime::DispatchError> {
-			let pool = Self::get_pool_address().unwrap();
-			let (token_1, token_2) = Self::get_token_ids().unwrap();
-			let liquidity_with_fees =
-				T::Tokens::balance_of(&pool, token_1)? + T::Tokens::balance_of(&pool, token_2)?;
-			Ok(liquidity_with_fees.checked_sub(&Self::get_total_liquidity().unwrap()).unwrap())
+		/// Reserves of the pool ordered as its `token_ids`.
+		pub fn pool_reserves(
+			pool_id: PoolId,
+			pool: &PoolInfoOf<T>,
+		) -> Result<(BalanceOf<T>, BalanceOf<T>), DispatchError> {
+			let (token_1, token_2) = pool.token_ids;
+			let reserves = Self::get_reserves(pool_id, pool, token_1, token_2)?;
+			Ok((reserves[0], reserves[1]))
 		}
 
-		fn initialized() -> Result<(), Error<T>> {
-			ensure!(Self::is_initialized(), <Error<T>>::Uninitilized);
-			Ok(())
+		/// Reorder `amounts` of `token_id` and its paired token as `token_ids` of the pool.
+		fn in_pool_order(
+			pool: &PoolInfoOf<T>,
+			token_id: TokenIdOf<T>,
+			amounts: (BalanceOf<T>, BalanceOf<T>),
+		) -> (BalanceOf<T>, BalanceOf<T>) {
+			if token_id == pool.token_ids.0 {
+				amounts
+			} else {
+				(amounts.1, amounts.0)
+			}
+		}
+
+		/// Move the fee taken from `input_amount` out of the reserves and share it between
+		/// liquidity providers, returns the fee.
+		fn collect_fee(
+			pool_id: PoolId,
+			token_id: TokenIdOf<T>,
+			input_amount: BalanceOf<T>,
+		) -> Result<BalanceOf<T>, DispatchError> {
+			let pool = Self::pool_info(pool_id)?;
+			let fee = input_amount.saturating_sub(pool.fee * input_amount);
+			if fee.is_zero() || pool.total_liquidity.is_zero() {
+				return Ok(fee)
+			}
+			CollectedFees::<T>::try_mutate(pool_id, token_id, |fees| -> Result<(), Error<T>> {
+				*fees = fees.checked_add(&fee).ok_or(Error::<T>::Overflow)?;
+				Ok(())
+			})?;
+			FeeGrowth::<T>::mutate(pool_id, token_id, |fee_growth| {
+				*fee_growth = fee_growth.saturating_add(FixedU128::saturating_from_rational(
+					fee.saturated_into::<u128>(),
+					pool.total_liquidity.saturated_into::<u128>(),
+				))
+			});
+			Ok(fee)
+		}
+
+		/// Settle the fees earned by the current liquidity of `owner`, has to be called before
+		/// the liquidity changes.
+		fn accrue_fees(pool_id: PoolId, owner: &T::AccountId) {
+			let liquidity = Self::get_liquidity(pool_id, owner);
+			FeePositions::<T>::mutate(pool_id, owner, |position| {
+				Self::update_fee_position(pool_id, position, liquidity)
+			});
 		}
 
-		fn uninitialized() -> Result<(), Error<T>> {
-			ensure!(!Self::is_initialized(), <Error<T>>::AlreadyInitialized);
+		fn update_fee_position(
+			pool_id: PoolId,
+			position: &mut FeePosition<BalanceOf<T>>,
+			liquidity: BalanceOf<T>,
+		) {
+			let (token_1, token_2) = match Self::get_pool(pool_id) {
+				Some(pool) => pool.token_ids,
+				None => return,
+			};
+			let fee_growth =
+				(Self::get_fee_growth(pool_id, token_1), Self::get_fee_growth(pool_id, token_2));
+			let liquidity = liquidity.saturated_into::<u128>();
+			let first_fee = fee_growth
+				.0
+				.saturating_sub(position.fee_growth_paid.0)
+				.saturating_mul_int(liquidity);
+			let second_fee = fee_growth
+				.1
+				.saturating_sub(position.fee_growth_paid.1)
+				.saturating_mul_int(liquidity);
+			position.owed.0 = position.owed.0.saturating_add(first_fee.saturated_into());
+			position.owed.1 = position.owed.1.saturating_add(second_fee.saturated_into());
+			position.fee_growth_paid = fee_growth;
+		}
+
+		/// Account liquidity mining rewards are paid from, it has to be funded with the reward token.
+		pub fn reward_account() -> T::AccountId {
+			T::PalletId::get().into_account()
+		}
+
+		/// Rewards `owner` could claim at the current block.
+		pub fn get_pending_reward(pool_id: PoolId, owner: &T::AccountId) -> BalanceOf<T> {
+			let mut stake = Self::get_stake(pool_id, owner);
+			let reward_per_share = Self::get_reward_per_share(pool_id)
+				.saturating_add(Self::reward_per_share_since_last_update(pool_id));
+			let accrued = reward_per_share
+				.saturating_sub(stake.reward_per_share_paid)
+				.saturating_mul_int(stake.amount.saturated_into::<u128>());
+			stake.pending.saturating_add(accrued.saturated_into())
+		}
+
+		/// Reward per staked unit emitted between `LastRewardBlock` and the current block.
+		fn reward_per_share_since_last_update(pool_id: PoolId) -> FixedU128 {
+			let total_staked = Self::get_total_staked(pool_id);
+			let schedule = match Self::get_reward_schedule(pool_id) {
+				Some(schedule) if !total_staked.is_zero() => schedule,
+				_ => return FixedU128::zero(),
+			};
+			let now = frame_system::Pallet::<T>::block_number();
+			let from = Self::get_last_reward_block(pool_id).max(schedule.start);
+			let to = now.min(schedule.end);
+			if to <= from {
+				return FixedU128::zero()
+			}
+			let reward = schedule
+				.reward_per_block
+				.saturated_into::<u128>()
+				.saturating_mul((to - from).saturated_into::<u128>());
+			FixedU128::saturating_from_rational(reward, total_staked.saturated_into::<u128>())
+		}
+
+		/// Bring `RewardPerShare` up to date with the current block, which is the only
+		/// bookkeeping emissions need: stakers settle against it lazily.
+		fn update_reward_per_share(pool_id: PoolId) {
+			let increase = Self::reward_per_share_since_last_update(pool_id);
+			if !increase.is_zero() {
+				RewardPerShare::<T>::mutate(pool_id, |reward_per_share| {
+					*reward_per_share = reward_per_share.saturating_add(increase)
+				});
+			}
+			LastRewardBlock::<T>::insert(pool_id, frame_system::Pallet::<T>::block_number());
+		}
+
+		fn accrue_reward(pool_id: PoolId, stake: &mut StakeInfo<BalanceOf<T>>) {
+			let reward_per_share = Self::get_reward_per_share(pool_id);
+			let accrued = reward_per_share
+				.saturating_sub(stake.reward_per_share_paid)
+				.saturating_mul_int(stake.amount.saturated_into::<u128>());
+			stake.pending = stake.pending.saturating_add(accrued.saturated_into());
+			stake.reward_per_share_paid = reward_per_share;
+		}
+
+		fn not_paused(pool_id: PoolId) -> Result<(), Error<T>> {
+			ensure!(!Self::is_paused(pool_id), <Error<T>>::Paused);
 			Ok(())
 		}
 
-		fn has_liquidity() -> Result<(), Error<T>> {
-			ensure!(
-				Self::get_total_liquidity().unwrap() != BalanceOf::<T>::default(),
-				<Error<T>>::NoLiquiudity
-			);
+		fn not_batched(pool_id: PoolId) -> Result<(), Error<T>> {
+			ensure!(!Self::is_batch_mode(pool_id), <Error<T>>::BatchModeEnabled);
 			Ok(())
 		}
 
-		fn is_initialized() -> bool {
-			Self::get_pool_address().is_some()
+		fn has_liquidity(pool: &PoolInfoOf<T>) -> Result<(), Error<T>> {
+			ensure!(!pool.total_liquidity.is_zero(), <Error<T>>::NoLiquiudity);
+			Ok(())
 		}
 
-		fn increase_liquidity(owner: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
-			TotalLiquidity::<T>::try_mutate(|liquidity| -> Result<(), Error<T>> {
-				let updated_liquidity =
-					liquidity.unwrap().checked_add(&amount).ok_or(Error::<T>::Overflow)?;
-				*liquidity = Some(updated_liquidity);
+		fn increase_liquidity(
+			pool_id: PoolId,
+			owner: &T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			Self::accrue_fees(pool_id, owner);
+			Pools::<T>::try_mutate(pool_id, |pool| -> Result<(), Error<T>> {
+				let pool = pool.as_mut().ok_or(Error::<T>::Uninitilized)?;
+				pool.total_liquidity =
+					pool.total_liquidity.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
 				Ok(())
 			})?;
-			Liquidity::<T>::try_mutate(owner, |liquidity| -> Result<(), Error<T>> {
-				let updated_liquidity =
-					liquidity.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
-				*liquidity = updated_liquidity;
+			Liquidity::<T>::try_mutate(pool_id, owner, |liquidity| -> Result<(), Error<T>> {
+				*liquidity = liquidity.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
 				Ok(())
 			})?;
 			Ok(())
 		}
 
-		fn decrease_liquidity(owner: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
-			TotalLiquidity::<T>::try_mutate(|liquidity| -> Result<(), Error<T>> {
-				let updated_liquidity =
-					liquidity.unwrap().checked_sub(&amount).ok_or(Error::<T>::Overflow)?;
-				*liquidity = Some(updated_liquidity);
+		fn decrease_liquidity(
+			pool_id: PoolId,
+			owner: &T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			ensure!(
+				Self::get_liquidity(pool_id, owner).checked_sub(&amount).unwrap_or_default() >=
+					Self::get_stake(pool_id, owner).amount,
+				Error::<T>::LiquidityIsStaked
+			);
+			Self::accrue_fees(pool_id, owner);
+			Pools::<T>::try_mutate(pool_id, |pool| -> Result<(), Error<T>> {
+				let pool = pool.as_mut().ok_or(Error::<T>::Uninitilized)?;
+				pool.total_liquidity =
+					pool.total_liquidity.checked_sub(&amount).ok_or(Error::<T>::Overflow)?;
 				Ok(())
 			})?;
-			Liquidity::<T>::try_mutate(owner, |liquidity| -> Result<(), Error<T>> {
-				let updated_liquidity =
-					liquidity.checked_sub(&amount).ok_or(Error::<T>::Overflow)?;
-				*liquidity = updated_liquidity;
+			Liquidity::<T>::try_mutate(pool_id, owner, |liquidity| -> Result<(), Error<T>> {
+				*liquidity = liquidity.checked_sub(&amount).ok_or(Error::<T>::Overflow)?;
 				Ok(())
 			})?;
 			Ok(())
@@ -0,0 +1,152 @@
+//! Storage migrations of the DEX pallet.
+
+use crate::pallet::{
+	BalanceOf, Config, Liquidity, NextPoolId, Pallet, PoolId, PoolIds, PoolInfo, Pools, TokenIdOf,
+};
+use codec::Decode;
+use frame_support::{
+	storage::migration::{storage_key_iter, take_storage_value},
+	traits::{Get, GetStorageVersion, OnRuntimeUpgrade, PalletInfoAccess, StorageVersion},
+	weights::Weight,
+	Blake2_128Concat,
+};
+use sp_std::{marker::PhantomData, vec::Vec};
+
+pub mod v1 {
+	use super::*;
+
+	/// Pool the single pool of the version 0 layout is migrated to.
+	pub const MIGRATED_POOL_ID: PoolId = 0;
+
+	/// Moves the single pool of the version 0 layout, together with the liquidity of its
+	/// providers, to the pool with id `MIGRATED_POOL_ID`.
+	///
+	/// The pool keeps its account, so the reserves stay where they are.
+	pub struct MigrateToMultiPool<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToMultiPool<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 0 {
+				return T::DbWeight::get().reads(1)
+			}
+			let migrated = migrate::<T>();
+			StorageVersion::new(1).put::<Pallet<T>>();
+			// every migrated item is read, removed and inserted under the new key
+			T::DbWeight::get().reads_writes(migrated + 1, 2 * migrated + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			use frame_support::{
+				storage::migration::get_storage_value, traits::OnRuntimeUpgradeHelpersExt,
+			};
+
+			let pallet = pallet_name::<T>();
+			let liquidity: Vec<(T::AccountId, BalanceOf<T>)> = storage_key_iter::<
+				T::AccountId,
+				BalanceOf<T>,
+				Blake2_128Concat,
+			>(pallet, b"Liquidity")
+			.collect();
+			Self::set_temp_storage(liquidity, "liquidity");
+			let account = get_storage_value::<T::AccountId>(pallet, b"PoolAddress", &[]);
+			let token_ids =
+				get_storage_value::<(TokenIdOf<T>, TokenIdOf<T>)>(pallet, b"TokenIds", &[]);
+			let total_liquidity = get_storage_value::<BalanceOf<T>>(pallet, b"TotalLiquidity", &[]);
+			if let (Some(account), Some(token_ids), Some(total_liquidity)) =
+				(account, token_ids, total_liquidity)
+			{
+				let reserves = reserves_of::<T>(&account, token_ids);
+				Self::set_temp_storage((account, token_ids, total_liquidity, reserves), "pool");
+			}
+			Ok(())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			use frame_support::{ensure, traits::OnRuntimeUpgradeHelpersExt};
+
+			ensure!(Pallet::<T>::on_chain_storage_version() == 1, "storage version not updated");
+			let liquidity: Vec<(T::AccountId, BalanceOf<T>)> =
+				Self::get_temp_storage("liquidity").ok_or("no liquidity recorded")?;
+			for (owner, amount) in liquidity {
+				ensure!(
+					Liquidity::<T>::get(MIGRATED_POOL_ID, &owner) == amount,
+					"liquidity not migrated"
+				);
+			}
+			let pool: Option<(
+				T::AccountId,
+				(TokenIdOf<T>, TokenIdOf<T>),
+				BalanceOf<T>,
+				(BalanceOf<T>, BalanceOf<T>),
+			)> = Self::get_temp_storage("pool");
+			if let Some((account, token_ids, total_liquidity, reserves)) = pool {
+				let info = Pools::<T>::get(MIGRATED_POOL_ID).ok_or("pool not migrated")?;
+				ensure!(info.account == account, "pool account not kept");
+				ensure!(info.token_ids == token_ids, "pool tokens not migrated");
+				ensure!(info.total_liquidity == total_liquidity, "total liquidity not migrated");
+				ensure!(
+					PoolIds::<T>::get(token_ids.0, token_ids.1) == Some(MIGRATED_POOL_ID) &&
+						PoolIds::<T>::get(token_ids.1, token_ids.0) == Some(MIGRATED_POOL_ID),
+					"pool not indexed by its tokens"
+				);
+				ensure!(reserves_of::<T>(&account, token_ids) == reserves, "pool reserves changed");
+			}
+			Ok(())
+		}
+	}
+
+	/// Returns the number of migrated storage items.
+	fn migrate<T: Config>() -> u64 {
+		let pallet = pallet_name::<T>();
+		let pool_id = MIGRATED_POOL_ID;
+		let mut migrated = 0u64;
+
+		let account = take_storage_value::<T::AccountId>(pallet, b"PoolAddress", &[]);
+		let token_ids =
+			take_storage_value::<(TokenIdOf<T>, TokenIdOf<T>)>(pallet, b"TokenIds", &[]);
+		let total_liquidity = take_storage_value::<BalanceOf<T>>(pallet, b"TotalLiquidity", &[]);
+		if let (Some(account), Some(token_ids), Some(total_liquidity)) =
+			(account, token_ids, total_liquidity)
+		{
+			Pools::<T>::insert(
+				pool_id,
+				PoolInfo { account, token_ids, total_liquidity, fee: T::Fee::get() },
+			);
+			PoolIds::<T>::insert(token_ids.0, token_ids.1, pool_id);
+			PoolIds::<T>::insert(token_ids.1, token_ids.0, pool_id);
+			NextPoolId::<T>::put(pool_id + 1);
+			migrated += 3;
+		}
+
+		// old entries have to be drained before the new ones are inserted under the same prefix
+		for (owner, amount) in drain_map::<T::AccountId, BalanceOf<T>>(pallet, b"Liquidity") {
+			Liquidity::<T>::insert(pool_id, owner, amount);
+			migrated += 1;
+		}
+
+		migrated
+	}
+
+	fn drain_map<K: Decode, V: Decode>(pallet: &[u8], item: &[u8]) -> Vec<(K, V)> {
+		storage_key_iter::<K, V, Blake2_128Concat>(pallet, item).drain().collect()
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn reserves_of<T: Config>(
+		account: &T::AccountId,
+		token_ids: (TokenIdOf<T>, TokenIdOf<T>),
+	) -> (BalanceOf<T>, BalanceOf<T>) {
+		use traits::Erc1155;
+
+		(
+			T::Tokens::balance_of(account, token_ids.0).unwrap_or_default(),
+			T::Tokens::balance_of(account, token_ids.1).unwrap_or_default(),
+		)
+	}
+
+	fn pallet_name<T: Config>() -> &'static [u8] {
+		<Pallet<T> as PalletInfoAccess>::name().as_bytes()
+	}
+}
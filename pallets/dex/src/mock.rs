@@ -1,5 +1,5 @@
 use crate as pallet_dex;
-use frame_support::parameter_types;
+use frame_support::{parameter_types, PalletId};
 use frame_system as system;
 use pallet_erc1155;
 use sp_core::H256;
@@ -21,7 +21,7 @@ frame_support::construct_runtime!(
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 		PalletErc1155: pallet_erc1155::{Pallet, Call, Storage, Event<T>},
-		Dex: pallet_dex::{Pallet, Call, Storage, Event<T>},
+		Dex: pallet_dex::{Pallet, Call, Storage, Event<T>, Config<T>},
 	}
 );
 
@@ -34,7 +34,7 @@ impl system::Config for Test {
 	type BaseCallFilter = frame_support::traits::Everything;
 	type BlockWeights = ();
 	type BlockLength = ();
-	type DbWeight = ();
+	type DbWeight = frame_support::weights::constants::RocksDbWeight;
 	type Origin = Origin;
 	type Call = Call;
 	type Index = u64;
@@ -76,24 +76,48 @@ impl pallet_balances::Config for Test {
 }
 
 parameter_types! {
-	pub const Decimals: u32 = 6;
+	pub const Erc1155PalletId: PalletId = PalletId(*b"erc/1155");
+	pub const WrappedTokenId: u32 = 100;
+	pub const NativeTokenId: u32 = 0;
+	pub const StringLimit: u32 = 50;
+	pub const MaxDataLen: u32 = 32;
 }
 
 impl pallet_erc1155::Config for Test {
 	type Event = Event;
 	type TokenId = u32;
 	type Balance = u128;
-	type Decimals = Decimals;
+	type Currency = Balances;
+	type PalletId = Erc1155PalletId;
+	type WrappedTokenId = WrappedTokenId;
+	type NativeTokenId = NativeTokenId;
+	type StringLimit = StringLimit;
+	type AdminOrigin = system::EnsureRoot<u64>;
+	type MaxDataLen = MaxDataLen;
+	type TransferHook = ();
 }
 
 parameter_types! {
 	pub const Fee: Perbill = Perbill::from_percent(99); // 1% per trade
+	pub const DexPalletId: PalletId = PalletId(*b"dex/farm");
+	pub const MaxSwapIntents: u32 = 3;
+	pub const CommitmentDeposit: u128 = 100;
+	pub const RevealWindow: u64 = 5;
+	pub const MaxCommitmentsPerBlock: u32 = 2;
 }
 
 impl pallet_dex::Config for Test {
 	type Event = Event;
 	type Tokens = PalletErc1155;
 	type Fee = Fee;
+	type AdminOrigin = system::EnsureRoot<u64>;
+	type PalletId = DexPalletId;
+	type PauseOrigin = system::EnsureRoot<u64>;
+	type MaxSwapIntents = MaxSwapIntents;
+	type Currency = Balances;
+	type CommitmentDeposit = CommitmentDeposit;
+	type RevealWindow = RevealWindow;
+	type MaxCommitmentsPerBlock = MaxCommitmentsPerBlock;
 }
 
 // Build genesis storage according to the mock runtime.
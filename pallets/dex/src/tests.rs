@@ -1,24 +1,37 @@
-use crate::{mock::*, Error};
-use frame_support::{assert_noop, assert_ok};
-use sp_runtime::Perbill;
+use crate::{mock::*, Error, PoolId, SwapQuote};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{Currency, OnFinalize, OnInitialize},
+};
+use sp_runtime::{DispatchError, FixedPointNumber, FixedU128, Perbill};
 use traits::Erc1155;
 
 const TOKEN_1_ID: u32 = 1;
 const TOKEN_2_ID: u32 = 2;
+const REWARD_TOKEN_ID: u32 = 3;
 const MIL: u128 = (10 as u128).pow(6);
 
-const POOL: u64 = 101;
+const POOL_ID: PoolId = 0;
 const ALICE: u64 = 1;
 const BOB: u64 = 2;
 const CHARLIE: u64 = 3;
 const MARRY: u64 = 5;
 const JOHN: u64 = 6;
 
+fn pool() -> u64 {
+	Dex::pool_account(POOL_ID)
+}
+
+fn total_liquidity() -> u128 {
+	Dex::get_pool(POOL_ID).unwrap().total_liquidity
+}
+
 fn init_tokens(total_supply: u128) {
 	assert_ok!(PalletErc1155::init(
 		Origin::signed(ALICE),
 		vec![TOKEN_1_ID, TOKEN_2_ID],
-		vec![total_supply, total_supply]
+		vec![total_supply * MIL, total_supply * MIL],
+		vec![6, 6]
 	));
 }
 
@@ -33,14 +46,14 @@ fn approve_and_transfer(from: u64, to: u64, amount: u128) {
 		from,
 		to,
 		vec![TOKEN_1_ID, TOKEN_2_ID],
-		vec![amount * MIL, amount * MIL]
+		vec![amount * MIL, amount * MIL],
+		Default::default()
 	));
 }
 
 fn init_dex(amount_0: u128, amount_1: u128) {
 	assert_ok!(Dex::init(
 		Origin::signed(ALICE),
-		POOL,
 		TOKEN_1_ID,
 		amount_0 * MIL,
 		TOKEN_2_ID,
@@ -48,19 +61,49 @@ fn init_dex(amount_0: u128, amount_1: u128) {
 	));
 }
 
+fn init_farming(reward_per_block: u128, start: u64, end: u64, funds: u128) {
+	assert_ok!(PalletErc1155::init(
+		Origin::signed(ALICE),
+		vec![REWARD_TOKEN_ID],
+		vec![funds * MIL],
+		vec![6]
+	));
+	approve(ALICE, Dex::reward_account());
+	assert_ok!(PalletErc1155::transfer_from_single(
+		Origin::signed(ALICE),
+		ALICE,
+		Dex::reward_account(),
+		REWARD_TOKEN_ID,
+		funds * MIL,
+		Default::default()
+	));
+	assert_ok!(Dex::set_reward_schedule(
+		Origin::root(),
+		POOL_ID,
+		REWARD_TOKEN_ID,
+		reward_per_block * MIL,
+		start,
+		end
+	));
+}
+
 #[test]
 fn init_should_work() {
 	new_test_ext().execute_with(|| {
 		let total_supply = 1000;
 		init_tokens(total_supply);
-		approve(ALICE, POOL);
+		approve(ALICE, pool());
 		init_dex(100, 1000);
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_1_ID), Ok(100_000_000));
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_2_ID), Ok(1000_000_000));
-		assert_eq!(Dex::get_pool_address(), Some(POOL));
-		assert_eq!(Dex::get_token_ids(), Some((TOKEN_1_ID, TOKEN_2_ID)));
-		assert_eq!(Dex::get_liquidity(ALICE), 1100 * MIL);
-		assert_eq!(Dex::get_liquidity(BOB), 0);
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_1_ID), Ok(100_000_000));
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_2_ID), Ok(1000_000_000));
+		let pool_info = Dex::get_pool(POOL_ID).unwrap();
+		assert_eq!(pool_info.account, pool());
+		assert_eq!(pool_info.token_ids, (TOKEN_1_ID, TOKEN_2_ID));
+		assert_eq!(pool_info.total_liquidity, 1100 * MIL);
+		assert_eq!(Dex::get_pool_id(TOKEN_2_ID, TOKEN_1_ID), Some(POOL_ID));
+		assert_eq!(Dex::get_next_pool_id(), 1);
+		assert_eq!(Dex::get_liquidity(POOL_ID, ALICE), 1100 * MIL);
+		assert_eq!(Dex::get_liquidity(POOL_ID, BOB), 0);
 	});
 }
 
@@ -69,7 +112,7 @@ fn init_should_fail_0() {
 	new_test_ext().execute_with(|| {
 		init_tokens(1000);
 		assert_noop!(
-			Dex::init(Origin::signed(ALICE), POOL, TOKEN_1_ID, 100 * MIL, TOKEN_2_ID, 1000 * MIL),
+			Dex::init(Origin::signed(ALICE), TOKEN_1_ID, 100 * MIL, TOKEN_2_ID, 1000 * MIL),
 			pallet_erc1155::Error::<Test>::TransferNotApproved
 		);
 	});
@@ -80,9 +123,9 @@ fn init_should_fail_1() {
 	new_test_ext().execute_with(|| {
 		init_tokens(1000);
 		approve_and_transfer(ALICE, BOB, 1000);
-		approve(ALICE, POOL);
+		approve(ALICE, pool());
 		assert_noop!(
-			Dex::init(Origin::signed(ALICE), POOL, TOKEN_1_ID, 100 * MIL, TOKEN_2_ID, 1000 * MIL),
+			Dex::init(Origin::signed(ALICE), TOKEN_1_ID, 100 * MIL, TOKEN_2_ID, 1000 * MIL),
 			pallet_erc1155::Error::<Test>::InsufficientFunds
 		);
 	});
@@ -92,7 +135,7 @@ fn init_should_fail_1() {
 fn init_should_fail_2() {
 	new_test_ext().execute_with(|| {
 		assert_noop!(
-			Dex::init(Origin::signed(ALICE), POOL, TOKEN_1_ID, 0, TOKEN_2_ID, 0),
+			Dex::init(Origin::signed(ALICE), TOKEN_1_ID, 0, TOKEN_2_ID, 0),
 			Error::<Test>::WrongInitialization
 		);
 	});
@@ -102,7 +145,7 @@ fn init_should_fail_2() {
 fn init_should_fail_3() {
 	new_test_ext().execute_with(|| {
 		assert_noop!(
-			Dex::init(Origin::signed(ALICE), 0, TOKEN_1_ID, 100, TOKEN_2_ID, 1000),
+			Dex::init(Origin::signed(ALICE), TOKEN_1_ID, 100, TOKEN_1_ID, 1000),
 			Error::<Test>::WrongInitialization
 		);
 	});
@@ -112,25 +155,101 @@ fn init_should_fail_3() {
 fn init_should_fail_4() {
 	new_test_ext().execute_with(|| {
 		init_tokens(1000);
-		approve(ALICE, POOL);
+		approve(ALICE, pool());
 		assert_noop!(
-			Dex::init(Origin::signed(ALICE), POOL, 3, 100 * MIL, 4, 1000 * MIL),
+			Dex::init(Origin::signed(ALICE), 3, 100 * MIL, 4, 1000 * MIL),
 			pallet_erc1155::Error::<Test>::Uninitilized
 		);
 	});
 }
 
+#[test]
+fn init_should_fail_5() {
+	new_test_ext().execute_with(|| {
+		init_tokens(1000);
+		approve(ALICE, pool());
+		init_dex(100, 100);
+		assert_noop!(
+			Dex::init(Origin::signed(ALICE), TOKEN_2_ID, 100 * MIL, TOKEN_1_ID, 100 * MIL),
+			Error::<Test>::AlreadyInitialized
+		);
+	});
+}
+
+#[test]
+fn pools_should_be_independent() {
+	new_test_ext().execute_with(|| {
+		init_tokens(1000);
+		assert_ok!(PalletErc1155::init(Origin::signed(ALICE), vec![3], vec![1000 * MIL], vec![6]));
+		let second_pool = Dex::pool_account(1);
+		assert_ne!(second_pool, pool());
+		approve(ALICE, pool());
+		approve(ALICE, second_pool);
+		init_dex(100, 100);
+		assert_ok!(Dex::init(Origin::signed(ALICE), TOKEN_1_ID, 100 * MIL, 3, 200 * MIL));
+		assert_eq!(Dex::get_pool_id(3, TOKEN_1_ID), Some(1));
+
+		assert_ok!(Dex::buy_token(Origin::signed(ALICE), 1, TOKEN_1_ID, 100 * MIL));
+		assert_eq!(PalletErc1155::balance_of(&second_pool, TOKEN_1_ID), Ok(200_000_000));
+		assert_eq!(PalletErc1155::balance_of(&second_pool, 3), Ok(100_502_513));
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_1_ID), Ok(100_000_000));
+		assert_eq!(Dex::get_collected_fees(1, TOKEN_1_ID), 1_000_000);
+		assert_eq!(Dex::get_collected_fees(POOL_ID, TOKEN_1_ID), 0);
+		assert_noop!(
+			Dex::buy_token(Origin::signed(ALICE), 1, TOKEN_2_ID, 10 * MIL),
+			Error::<Test>::WrongTokenId
+		);
+		assert_noop!(
+			Dex::buy_token(Origin::signed(ALICE), 2, TOKEN_1_ID, 10 * MIL),
+			Error::<Test>::Uninitilized
+		);
+
+		assert_ok!(Dex::pause_pool(Origin::root(), 1));
+		assert_ok!(Dex::buy_token(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, 10 * MIL));
+	});
+}
+
+#[test]
+fn genesis_pools_should_work() {
+	use frame_support::traits::GenesisBuild;
+
+	new_test_ext().execute_with(|| {
+		init_tokens(1000);
+		crate::GenesisConfig::<Test> {
+			pools: vec![(
+				ALICE,
+				TOKEN_1_ID,
+				100 * MIL,
+				TOKEN_2_ID,
+				200 * MIL,
+				Perbill::from_percent(98),
+			)],
+		}
+		.build();
+		assert_eq!(Dex::get_pool(POOL_ID).unwrap().fee, Perbill::from_percent(98));
+		assert_eq!(Dex::get_liquidity(POOL_ID, ALICE), 300 * MIL);
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_1_ID), Ok(100_000_000));
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_2_ID), Ok(200_000_000));
+
+		approve(ALICE, pool());
+		assert_ok!(Dex::buy_token(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, 100 * MIL)); // 0.98 * 100 * 200 / (100 + 98) = 98.989898
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_2_ID), Ok(101_010_102));
+		assert_eq!(Dex::get_collected_fees(POOL_ID, TOKEN_1_ID), 2_000_000);
+	});
+}
+
 #[test]
 fn buy_token_should_work() {
 	new_test_ext().execute_with(|| {
 		init_tokens(1000);
-		approve(ALICE, POOL);
+		approve(ALICE, pool());
 		init_dex(100, 1000);
-		assert_ok!(Dex::buy_token(Origin::signed(ALICE), TOKEN_1_ID, 100 * MIL));
+		assert_ok!(Dex::buy_token(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, 100 * MIL));
 		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_1_ID), Ok(800_000_000));
 		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_2_ID), Ok(497_487_437)); // 0.99 * 100 * 1000 / (100 + 99) = 497.487437
-		assert_ok!(Dex::buy_token(Origin::signed(ALICE), TOKEN_2_ID, 235 * MIL));
-		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_1_ID), Ok(863_292_123)); // 0.99 * 235 * 200 / (502.512563 + 232.65) = 63.292123
+		assert_ok!(Dex::buy_token(Origin::signed(ALICE), POOL_ID, TOKEN_2_ID, 235 * MIL));
+		// 1 token of the first trade is a fee, which is not a part of reserves
+		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_1_ID), Ok(862_975_663)); // 0.99 * 235 * 199 / (502.512563 + 232.65) = 62.975663
 		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_2_ID), Ok(262_487_437));
 	});
 }
@@ -139,13 +258,542 @@ fn buy_token_should_work() {
 fn buy_token_should_fail() {
 	new_test_ext().execute_with(|| {
 		init_tokens(1000);
-		approve(ALICE, POOL);
+		approve(ALICE, pool());
+		init_dex(100, 1000);
+		assert_ok!(Dex::withdraw(Origin::signed(ALICE), POOL_ID, 100)); // 100%
+		assert_noop!(
+			Dex::buy_token(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, 1 * MIL),
+			Error::<Test>::NoLiquiudity
+		);
+	});
+}
+
+#[test]
+fn buy_token_should_spend_allowance() {
+	new_test_ext().execute_with(|| {
+		init_tokens(1000);
+		assert_ok!(PalletErc1155::approve(Origin::signed(ALICE), pool(), TOKEN_1_ID, 200 * MIL));
+		assert_ok!(PalletErc1155::approve(Origin::signed(ALICE), pool(), TOKEN_2_ID, 1000 * MIL));
+		init_dex(100, 1000);
+		assert_eq!(PalletErc1155::allowance(&ALICE, &pool(), TOKEN_1_ID), 100 * MIL);
+		assert_eq!(PalletErc1155::allowance(&ALICE, &pool(), TOKEN_2_ID), 0);
+		assert_ok!(Dex::buy_token(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, 100 * MIL));
+		assert_eq!(PalletErc1155::allowance(&ALICE, &pool(), TOKEN_1_ID), 0);
+		assert_noop!(
+			Dex::buy_token(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, 1 * MIL),
+			pallet_erc1155::Error::<Test>::TransferNotApproved
+		);
+	});
+}
+
+#[test]
+fn buy_token_should_respect_frozen_tokens() {
+	new_test_ext().execute_with(|| {
+		init_tokens(1000);
+		approve(ALICE, pool());
+		init_dex(100, 1000);
+		assert_ok!(PalletErc1155::freeze(Origin::signed(ALICE), TOKEN_1_ID, ALICE));
+		assert_noop!(
+			Dex::buy_token(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, 1 * MIL),
+			pallet_erc1155::Error::<Test>::AccountFrozen
+		);
+		assert_ok!(PalletErc1155::thaw(Origin::signed(ALICE), TOKEN_1_ID, ALICE));
+		// the pool can not pay out a frozen token
+		assert_ok!(PalletErc1155::freeze_token(Origin::signed(ALICE), TOKEN_2_ID));
+		assert_noop!(
+			Dex::buy_token(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, 1 * MIL),
+			pallet_erc1155::Error::<Test>::TokenFrozen
+		);
+		assert_ok!(PalletErc1155::thaw_token(Origin::signed(ALICE), TOKEN_2_ID));
+		assert_ok!(Dex::buy_token(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, 1 * MIL));
+	});
+}
+
+#[test]
+fn quote_buy_should_report_price_impact() {
+	new_test_ext().execute_with(|| {
+		init_tokens(1000);
+		approve(ALICE, pool());
+		init_dex(99, 100);
+		// the swapped 99 tokens double the reserve, so the price drops to (1/2)^2 of its value
+		assert_eq!(
+			Dex::quote_buy(POOL_ID, TOKEN_1_ID, 100 * MIL),
+			Ok(SwapQuote {
+				amount_in: 100 * MIL,
+				amount_out: 50 * MIL,
+				fee: 1 * MIL,
+				price_impact: Perbill::from_percent(75),
+			})
+		);
+		assert_ok!(Dex::buy_token(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, 100 * MIL));
+		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_2_ID), Ok(950 * MIL));
+		assert_noop!(Dex::quote_buy(POOL_ID, 3, 100 * MIL), Error::<Test>::WrongTokenId);
+	});
+}
+
+#[test]
+fn quotes_should_fail_on_overflow() {
+	new_test_ext().execute_with(|| {
+		let amount = 1u128 << 80;
+		assert_ok!(PalletErc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_1_ID, TOKEN_2_ID],
+			vec![amount, amount],
+			vec![6, 6]
+		));
+		approve(ALICE, pool());
+		assert_ok!(Dex::init(
+			Origin::signed(ALICE),
+			TOKEN_1_ID,
+			amount / 2,
+			TOKEN_2_ID,
+			amount / 2
+		));
+		// the product of the input and the output reserve does not fit into u128
+		assert_noop!(Dex::quote_buy(POOL_ID, TOKEN_1_ID, amount / 2), Error::<Test>::Overflow);
+		assert_noop!(
+			Dex::quote_deposit_single_token(POOL_ID, TOKEN_1_ID, amount / 2),
+			Error::<Test>::Overflow
+		);
+		assert_noop!(
+			Dex::buy_token(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, amount / 2),
+			Error::<Test>::Overflow
+		);
+	});
+}
+
+#[test]
+fn max_price_impact_should_work() {
+	new_test_ext().execute_with(|| {
+		init_tokens(1000);
+		approve(ALICE, pool());
+		init_dex(99, 100);
+		assert_noop!(
+			Dex::set_max_price_impact(Origin::signed(ALICE), POOL_ID, None),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Dex::set_max_price_impact(Origin::root(), 1, None),
+			Error::<Test>::Uninitilized
+		);
+		assert_ok!(Dex::set_max_price_impact(
+			Origin::root(),
+			POOL_ID,
+			Some(Perbill::from_percent(50))
+		));
+		assert_noop!(
+			Dex::buy_token(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, 100 * MIL),
+			Error::<Test>::PriceImpactTooHigh
+		);
+		let quote = Dex::quote_deposit_single_token(POOL_ID, TOKEN_1_ID, 500 * MIL).unwrap();
+		assert!(quote.price_impact > Perbill::from_percent(50));
+		assert_noop!(
+			Dex::deposit_single_token(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, 500 * MIL),
+			Error::<Test>::PriceImpactTooHigh
+		);
+		let quote = Dex::quote_withdraw_single_token(POOL_ID, &ALICE, TOKEN_1_ID, 100).unwrap();
+		assert!(quote.price_impact > Perbill::from_percent(50));
+		assert_noop!(
+			Dex::withdraw_single_token(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, 100),
+			Error::<Test>::PriceImpactTooHigh
+		);
+		assert_ok!(Dex::buy_token(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, 10 * MIL));
+
+		assert_ok!(Dex::set_max_price_impact(Origin::root(), POOL_ID, None));
+		assert_ok!(Dex::buy_token(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, 100 * MIL));
+	});
+}
+
+fn init_batch_pool() {
+	init_tokens(1000);
+	approve_and_transfer(ALICE, BOB, 100);
+	approve_and_transfer(ALICE, CHARLIE, 100);
+	approve(ALICE, pool());
+	approve(BOB, pool());
+	approve(CHARLIE, pool());
+	init_dex(100, 100);
+	assert_ok!(Dex::set_batch_mode(Origin::root(), POOL_ID, true));
+}
+
+#[test]
+fn batch_should_settle_at_clearing_price() {
+	new_test_ext().execute_with(|| {
+		init_batch_pool();
+		assert_ok!(Dex::submit_swap_intent(Origin::signed(BOB), POOL_ID, TOKEN_1_ID, 10 * MIL, 0));
+		assert_ok!(Dex::submit_swap_intent(
+			Origin::signed(CHARLIE),
+			POOL_ID,
+			TOKEN_2_ID,
+			5 * MIL,
+			0
+		));
+		// intents are not a part of the reserves until they are settled
+		let pool_info = Dex::get_pool(POOL_ID).unwrap();
+		assert_eq!(Dex::pool_reserves(POOL_ID, &pool_info), Ok((100 * MIL, 100 * MIL)));
+		Dex::on_finalize(1);
+		// both sides get the price (100 + 4.95) / (100 + 9.9) = 0.954959 of the first token
+		assert_eq!(PalletErc1155::balance_of(&BOB, TOKEN_2_ID), Ok(109_454_094));
+		assert_eq!(PalletErc1155::balance_of(&CHARLIE, TOKEN_1_ID), Ok(105_183_468)); // 4.95 / 0.954959
+		let (reserve_0, reserve_1) = Dex::pool_reserves(POOL_ID, &pool_info).unwrap();
+		assert_eq!((reserve_0, reserve_1), (104_716_532, 95_495_906));
+		assert!(reserve_0 * reserve_1 >= 100 * MIL * 100 * MIL);
+		assert_eq!(Dex::get_collected_fees(POOL_ID, TOKEN_1_ID), 100_000);
+		assert_eq!(Dex::get_collected_fees(POOL_ID, TOKEN_2_ID), 50_000);
+		assert!(Dex::get_swap_intents(POOL_ID).is_empty());
+	});
+}
+
+#[test]
+fn batch_should_refund_intents_below_limit() {
+	new_test_ext().execute_with(|| {
+		init_batch_pool();
+		System::set_block_number(1);
+		assert_ok!(Dex::submit_swap_intent(
+			Origin::signed(BOB),
+			POOL_ID,
+			TOKEN_1_ID,
+			10 * MIL,
+			9 * MIL
+		));
+		// would get 5.183468 at the price of the whole batch
+		assert_ok!(Dex::submit_swap_intent(
+			Origin::signed(CHARLIE),
+			POOL_ID,
+			TOKEN_2_ID,
+			5 * MIL,
+			5_200_000
+		));
+		assert_eq!(PalletErc1155::balance_of(&CHARLIE, TOKEN_2_ID), Ok(95 * MIL));
+		Dex::on_finalize(1);
+		// intents are filled all or nothing, the whole input of CHARLIE is refunded
+		assert_eq!(PalletErc1155::balance_of(&CHARLIE, TOKEN_2_ID), Ok(100 * MIL));
+		assert_eq!(PalletErc1155::balance_of(&CHARLIE, TOKEN_1_ID), Ok(100 * MIL));
+		// without the refunded intent the price is 100 / 109.9
+		assert_eq!(PalletErc1155::balance_of(&BOB, TOKEN_2_ID), Ok(109_008_189));
+		let events = System::events().into_iter().map(|record| record.event).collect::<Vec<_>>();
+		assert_eq!(
+			events[events.len() - 3..],
+			[
+				Event::Dex(crate::Event::SwapIntentRefunded {
+					who: CHARLIE,
+					pool_id: POOL_ID,
+					token_id: TOKEN_2_ID,
+					amount: 5 * MIL,
+				}),
+				Event::Dex(crate::Event::TokenBought {
+					who: BOB,
+					pool_id: POOL_ID,
+					sold_token_id: TOKEN_1_ID,
+					sold_amount: 10 * MIL,
+					bought_token_id: TOKEN_2_ID,
+					bought_amount: 9_008_189,
+					fee_paid: 100_000,
+					reserves: (109_900_000, 90_991_811),
+				}),
+				Event::Dex(crate::Event::BatchSettled {
+					pool_id: POOL_ID,
+					clearing_price: FixedU128::saturating_from_rational(100 * MIL, 109_900_000),
+					reserves: (109_900_000, 90_991_811),
+				}),
+			]
+		);
+	});
+}
+
+#[test]
+fn batch_should_refund_intents_of_paused_pool() {
+	new_test_ext().execute_with(|| {
+		init_batch_pool();
+		assert_ok!(Dex::submit_swap_intent(Origin::signed(BOB), POOL_ID, TOKEN_1_ID, 10 * MIL, 0));
+		assert_ok!(Dex::pause_pool(Origin::root(), POOL_ID));
+		Dex::on_finalize(1);
+		assert_eq!(PalletErc1155::balance_of(&BOB, TOKEN_1_ID), Ok(100 * MIL));
+		assert_eq!(PalletErc1155::balance_of(&BOB, TOKEN_2_ID), Ok(100 * MIL));
+		assert_eq!(Dex::get_batched_amount(POOL_ID, TOKEN_1_ID), 0);
+	});
+}
+
+#[test]
+fn batch_should_keep_failed_refunds_claimable() {
+	new_test_ext().execute_with(|| {
+		init_batch_pool();
+		System::set_block_number(1);
+		assert_ok!(Dex::submit_swap_intent(Origin::signed(BOB), POOL_ID, TOKEN_1_ID, 10 * MIL, 0));
+		assert_ok!(Dex::submit_swap_intent(
+			Origin::signed(CHARLIE),
+			POOL_ID,
+			TOKEN_2_ID,
+			5 * MIL,
+			0
+		));
+		// the pool can neither pay out nor refund the second token
+		assert_ok!(PalletErc1155::freeze(Origin::signed(ALICE), TOKEN_2_ID, pool()));
+		Dex::on_finalize(1);
+		assert_eq!(PalletErc1155::balance_of(&BOB, TOKEN_1_ID), Ok(100 * MIL));
+		assert_eq!(PalletErc1155::balance_of(&CHARLIE, TOKEN_2_ID), Ok(95 * MIL));
+		assert_eq!(Dex::get_unclaimed_refund((POOL_ID, CHARLIE, TOKEN_2_ID)), 5 * MIL);
+		System::assert_last_event(Event::Dex(crate::Event::SwapIntentRefundFailed {
+			who: CHARLIE,
+			pool_id: POOL_ID,
+			token_id: TOKEN_2_ID,
+			amount: 5 * MIL,
+		}));
+		// the unclaimed refund is not a part of the reserves
+		let pool_info = Dex::get_pool(POOL_ID).unwrap();
+		assert_eq!(Dex::pool_reserves(POOL_ID, &pool_info), Ok((100 * MIL, 100 * MIL)));
+
+		assert_noop!(
+			Dex::claim_refund(Origin::signed(CHARLIE), POOL_ID, TOKEN_2_ID),
+			pallet_erc1155::Error::<Test>::AccountFrozen
+		);
+		assert_ok!(PalletErc1155::thaw(Origin::signed(ALICE), TOKEN_2_ID, pool()));
+		assert_ok!(Dex::claim_refund(Origin::signed(CHARLIE), POOL_ID, TOKEN_2_ID));
+		assert_eq!(PalletErc1155::balance_of(&CHARLIE, TOKEN_2_ID), Ok(100 * MIL));
+		assert_eq!(Dex::get_batched_amount(POOL_ID, TOKEN_2_ID), 0);
+		assert_eq!(Dex::pool_reserves(POOL_ID, &pool_info), Ok((100 * MIL, 100 * MIL)));
+		assert_noop!(
+			Dex::claim_refund(Origin::signed(CHARLIE), POOL_ID, TOKEN_2_ID),
+			Error::<Test>::NoRefundToClaim
+		);
+	});
+}
+
+#[test]
+fn batch_settlement_weight_should_be_registered() {
+	new_test_ext().execute_with(|| {
+		init_tokens(1000);
+		approve(ALICE, pool());
+		init_dex(100, 100);
+		let idle = Dex::on_initialize(1);
+		assert_ok!(Dex::set_batch_mode(Origin::root(), POOL_ID, true));
+		assert_ok!(Dex::set_batch_mode(Origin::root(), POOL_ID, true));
+		assert_eq!(Dex::get_batch_pool_count(), 1);
+		assert!(Dex::on_initialize(1) > idle);
+		assert_ok!(Dex::set_batch_mode(Origin::root(), POOL_ID, false));
+		assert_eq!(Dex::get_batch_pool_count(), 0);
+		assert_eq!(Dex::on_initialize(1), idle);
+	});
+}
+
+#[test]
+fn batch_mode_should_fail() {
+	new_test_ext().execute_with(|| {
+		init_tokens(1000);
+		approve(ALICE, pool());
+		init_dex(100, 100);
+		assert_noop!(
+			Dex::submit_swap_intent(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, 1 * MIL, 0),
+			Error::<Test>::NotInBatchMode
+		);
+		assert_noop!(
+			Dex::set_batch_mode(Origin::signed(ALICE), POOL_ID, true),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(Dex::set_batch_mode(Origin::root(), POOL_ID, true));
+		assert_noop!(
+			Dex::buy_token(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, 1 * MIL),
+			Error::<Test>::BatchModeEnabled
+		);
+		assert_noop!(
+			Dex::deposit_single_token(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, 1 * MIL),
+			Error::<Test>::BatchModeEnabled
+		);
+		assert_noop!(
+			Dex::submit_swap_intent(Origin::signed(ALICE), POOL_ID, 3, 1 * MIL, 0),
+			Error::<Test>::WrongTokenId
+		);
+		for _ in 0..3 {
+			assert_ok!(Dex::submit_swap_intent(
+				Origin::signed(ALICE),
+				POOL_ID,
+				TOKEN_1_ID,
+				1 * MIL,
+				0
+			));
+		}
+		assert_noop!(
+			Dex::submit_swap_intent(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, 1 * MIL, 0),
+			Error::<Test>::TooManySwapIntents
+		);
+	});
+}
+
+#[test]
+fn commit_reveal_swap_should_work() {
+	new_test_ext().execute_with(|| {
+		init_tokens(1000);
+		approve(ALICE, pool());
+		init_dex(100, 1000);
+		Balances::make_free_balance_be(&ALICE, 1000);
+		System::set_block_number(1);
+		let salt = [1; 32];
+		let commitment =
+			Dex::swap_commitment(&ALICE, POOL_ID, TOKEN_1_ID, 100 * MIL, 490 * MIL, salt);
+		assert_ok!(Dex::commit_swap(Origin::signed(ALICE), commitment));
+		assert_eq!(Balances::reserved_balance(&ALICE), 100);
+		assert_noop!(
+			Dex::reveal_swap(
+				Origin::signed(ALICE),
+				POOL_ID,
+				TOKEN_1_ID,
+				100 * MIL,
+				490 * MIL,
+				salt
+			),
+			Error::<Test>::RevealTooEarly
+		);
+
+		System::set_block_number(2);
+		assert_noop!(
+			Dex::reveal_swap(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, 100 * MIL, 0, salt),
+			Error::<Test>::UnknownCommitment
+		);
+		assert_ok!(Dex::reveal_swap(
+			Origin::signed(ALICE),
+			POOL_ID,
+			TOKEN_1_ID,
+			100 * MIL,
+			490 * MIL,
+			salt
+		));
+		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_2_ID), Ok(497_487_437));
+		assert_eq!(Balances::reserved_balance(&ALICE), 0);
+		assert_eq!(Dex::get_commitment(ALICE, commitment), None);
+		System::assert_last_event(Event::Dex(crate::Event::SwapRevealed {
+			who: ALICE,
+			commitment,
+		}));
+		// revealed commitments do not expire
+		Dex::on_finalize(6);
+		assert_eq!(Balances::free_balance(&ALICE), 1000);
+	});
+}
+
+#[test]
+fn reveal_swap_should_check_slippage() {
+	new_test_ext().execute_with(|| {
+		init_tokens(1000);
+		approve(ALICE, pool());
+		init_dex(100, 1000);
+		Balances::make_free_balance_be(&ALICE, 1000);
+		System::set_block_number(1);
+		let salt = [2; 32];
+		let commitment =
+			Dex::swap_commitment(&ALICE, POOL_ID, TOKEN_1_ID, 100 * MIL, 500 * MIL, salt);
+		assert_ok!(Dex::commit_swap(Origin::signed(ALICE), commitment));
+		System::set_block_number(2);
+		assert_noop!(
+			Dex::reveal_swap(
+				Origin::signed(ALICE),
+				POOL_ID,
+				TOKEN_1_ID,
+				100 * MIL,
+				500 * MIL,
+				salt
+			),
+			Error::<Test>::SlippageExceeded
+		);
+		assert!(Dex::get_commitment(ALICE, commitment).is_some());
+	});
+}
+
+#[test]
+fn unrevealed_commitment_should_expire() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&ALICE, 1000);
+		System::set_block_number(1);
+		let commitment = Dex::swap_commitment(&ALICE, POOL_ID, TOKEN_1_ID, 1, 0, [3; 32]);
+		assert_ok!(Dex::commit_swap(Origin::signed(ALICE), commitment));
+		Dex::on_finalize(5);
+		assert!(Dex::get_commitment(ALICE, commitment).is_some());
+		Dex::on_finalize(6);
+		assert_eq!(Dex::get_commitment(ALICE, commitment), None);
+		assert_eq!(Balances::reserved_balance(&ALICE), 0);
+		assert_eq!(Balances::free_balance(&ALICE), 900);
+		System::assert_last_event(Event::Dex(crate::Event::CommitmentExpired {
+			who: ALICE,
+			commitment,
+			deposit: 100,
+		}));
+	});
+}
+
+#[test]
+fn recommitted_swap_should_not_expire_early() {
+	new_test_ext().execute_with(|| {
+		init_tokens(1000);
+		approve(ALICE, pool());
 		init_dex(100, 1000);
-		assert_ok!(Dex::withdraw(Origin::signed(ALICE), 100)); // 100%
+		Balances::make_free_balance_be(&ALICE, 1000);
+		System::set_block_number(1);
+		let salt = [4; 32];
+		let commitment = Dex::swap_commitment(&ALICE, POOL_ID, TOKEN_1_ID, 1 * MIL, 0, salt);
+		assert_ok!(Dex::commit_swap(Origin::signed(ALICE), commitment));
+		System::set_block_number(2);
+		assert_ok!(Dex::reveal_swap(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, 1 * MIL, 0, salt));
+		assert!(Dex::get_commitment_expiries(6).is_empty());
+
+		System::set_block_number(3);
+		assert_ok!(Dex::commit_swap(Origin::signed(ALICE), commitment));
+		// the window of the first commitment ends, the new one is kept until block 8
+		Dex::on_finalize(6);
+		assert!(Dex::get_commitment(ALICE, commitment).is_some());
+		assert_eq!(Balances::reserved_balance(&ALICE), 100);
+		System::set_block_number(7);
+		assert_ok!(Dex::reveal_swap(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, 1 * MIL, 0, salt));
+		assert_eq!(Balances::free_balance(&ALICE), 1000);
+	});
+}
+
+#[test]
+fn commit_swap_should_fail() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&ALICE, 1000);
+		System::set_block_number(1);
+		let commitment = |salt| Dex::swap_commitment(&ALICE, POOL_ID, TOKEN_1_ID, 1, 0, salt);
+		assert_ok!(Dex::commit_swap(Origin::signed(ALICE), commitment([1; 32])));
 		assert_noop!(
-			Dex::buy_token(Origin::signed(ALICE), TOKEN_1_ID, 1 * MIL),
+			Dex::commit_swap(Origin::signed(ALICE), commitment([1; 32])),
+			Error::<Test>::DuplicateCommitment
+		);
+		assert_noop!(
+			Dex::commit_swap(Origin::signed(BOB), commitment([2; 32])),
+			pallet_balances::Error::<Test>::InsufficientBalance
+		);
+		assert_ok!(Dex::commit_swap(Origin::signed(ALICE), commitment([2; 32])));
+		assert_noop!(
+			Dex::commit_swap(Origin::signed(ALICE), commitment([3; 32])),
+			Error::<Test>::TooManyCommitments
+		);
+		assert_eq!(Balances::reserved_balance(&ALICE), 200);
+	});
+}
+
+#[test]
+fn swap_exact_should_work() {
+	new_test_ext().execute_with(|| {
+		init_tokens(1000);
+		approve_and_transfer(ALICE, BOB, 200);
+		approve(ALICE, pool());
+		init_dex(100, 500);
+		// the swaps need no approval of the pool
+		assert_eq!(Dex::quote_exact_out(POOL_ID, TOKEN_1_ID, 497_487_437 / 2), Ok(100 * MIL));
+		assert_noop!(
+			Dex::swap_exact_out(&BOB, POOL_ID, TOKEN_1_ID, 497_487_437 / 2, 100 * MIL - 1),
+			Error::<Test>::SlippageExceeded
+		);
+		assert_noop!(
+			Dex::swap_exact_out(&BOB, POOL_ID, TOKEN_1_ID, 500 * MIL, 1000 * MIL),
 			Error::<Test>::NoLiquiudity
 		);
+		assert_eq!(
+			Dex::swap_exact_out(&BOB, POOL_ID, TOKEN_1_ID, 497_487_437 / 2, 100 * MIL),
+			Ok(100 * MIL)
+		);
+		assert_eq!(PalletErc1155::balance_of(&BOB, TOKEN_1_ID), Ok(100 * MIL));
+		assert_eq!(PalletErc1155::balance_of(&BOB, TOKEN_2_ID), Ok(200 * MIL + 248_743_718));
+		assert_ok!(Dex::swap_exact_in(&BOB, POOL_ID, TOKEN_2_ID, 100 * MIL));
+		assert_eq!(PalletErc1155::balance_of(&BOB, TOKEN_2_ID), Ok(100 * MIL + 248_743_718));
 	});
 }
 
@@ -154,17 +802,17 @@ fn deposit_should_work_1() {
 	new_test_ext().execute_with(|| {
 		init_tokens(1000);
 		approve_and_transfer(ALICE, BOB, 300);
-		approve(ALICE, POOL);
-		approve(BOB, POOL);
+		approve(ALICE, pool());
+		approve(BOB, pool());
 		init_dex(100, 200); // 100x200
-		assert_ok!(Dex::deposit(Origin::signed(BOB), TOKEN_1_ID, 100 * MIL));
-		assert_eq!(Dex::get_liquidity(BOB), 300_000_000);
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_1_ID), Ok(200_000_000));
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_2_ID), Ok(400_000_000));
-		assert_ok!(Dex::deposit(Origin::signed(BOB), TOKEN_2_ID, 50 * MIL));
-		assert_eq!(Dex::get_liquidity(BOB), 375_000_000);
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_1_ID), Ok(225_000_000));
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_2_ID), Ok(450_000_000));
+		assert_ok!(Dex::deposit(Origin::signed(BOB), POOL_ID, TOKEN_1_ID, 100 * MIL));
+		assert_eq!(Dex::get_liquidity(POOL_ID, BOB), 300_000_000);
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_1_ID), Ok(200_000_000));
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_2_ID), Ok(400_000_000));
+		assert_ok!(Dex::deposit(Origin::signed(BOB), POOL_ID, TOKEN_2_ID, 50 * MIL));
+		assert_eq!(Dex::get_liquidity(POOL_ID, BOB), 375_000_000);
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_1_ID), Ok(225_000_000));
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_2_ID), Ok(450_000_000));
 	});
 }
 
@@ -173,14 +821,28 @@ fn deposit_single_token_should_work_1() {
 	new_test_ext().execute_with(|| {
 		init_tokens(1000);
 		approve_and_transfer(ALICE, BOB, 300);
-		approve(ALICE, POOL);
-		approve(BOB, POOL);
+		approve(ALICE, pool());
+		approve(BOB, pool());
 		init_dex(100, 100);
-		assert_ok!(Dex::deposit_single_token(Origin::signed(BOB), TOKEN_1_ID, 10 * MIL));
+		System::set_block_number(1);
+		assert_ok!(Dex::deposit_single_token(Origin::signed(BOB), POOL_ID, TOKEN_1_ID, 10 * MIL));
 		// due to token swap(and fee) the liquidity of BOB is 9.526565 instead of 10, in case of deposit of 5 tokens
-		assert_eq!(Dex::get_liquidity(BOB), 9_526_565);
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_1_ID), Ok(110_000_000));
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_2_ID), Ok(100_000_000));
+		assert_eq!(Dex::get_liquidity(POOL_ID, BOB), 9_526_565);
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_1_ID), Ok(110_000_000));
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_2_ID), Ok(100_000_000));
+		assert_eq!(
+			System::events().into_iter().map(|record| record.event).collect::<Vec<_>>(),
+			vec![Event::Dex(crate::Event::SingleTokenDeposited {
+				who: BOB,
+				pool_id: POOL_ID,
+				token_id: TOKEN_1_ID,
+				amount: 10 * MIL,
+				swapped_amount: 4_900_000,
+				fee_paid: 49_000,
+				liquidity_minted: 9_526_565,
+				reserves: (109_951_000, 100_000_000),
+			})]
+		);
 	});
 }
 
@@ -189,14 +851,14 @@ fn withdraw_should_work_1() {
 	new_test_ext().execute_with(|| {
 		init_tokens(1100);
 		approve_and_transfer(ALICE, BOB, 100);
-		approve(ALICE, POOL);
-		approve(BOB, POOL);
+		approve(ALICE, pool());
+		approve(BOB, pool());
 		init_dex(100, 1000);
-		assert_ok!(Dex::buy_token(Origin::signed(BOB), TOKEN_1_ID, 100 * MIL)); // 0.99 * 100 * 1000 / (100 + 99) = 497.487437
-		assert_ok!(Dex::withdraw(Origin::signed(ALICE), 15)); // 15%
-		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_1_ID), Ok(930_000_000)); // 800 + 200 * 0.15 = 830
+		assert_ok!(Dex::buy_token(Origin::signed(BOB), POOL_ID, TOKEN_1_ID, 100 * MIL)); // 0.99 * 100 * 1000 / (100 + 99) = 497.487437
+		assert_ok!(Dex::withdraw(Origin::signed(ALICE), POOL_ID, 15)); // 15%
+		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_1_ID), Ok(929_850_000)); // 900 + 199 * 0.15 = 929.85
 		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_2_ID), Ok(753_768_84)); // 0 + 502.512563 * 0.15 = 575.376884
-		assert_eq!(Dex::get_liquidity(ALICE), 935_000_000); // ~ 1100 * 0.85 = 935
+		assert_eq!(Dex::get_liquidity(POOL_ID, ALICE), 935_000_000); // ~ 1100 * 0.85 = 935
 	});
 }
 
@@ -205,17 +867,17 @@ fn withdraw_should_work_2() {
 	new_test_ext().execute_with(|| {
 		init_tokens(1100);
 		approve_and_transfer(ALICE, BOB, 100);
-		approve(ALICE, POOL);
-		approve(BOB, POOL);
+		approve(ALICE, pool());
+		approve(BOB, pool());
 		init_dex(100, 1000);
-		assert_ok!(Dex::buy_token(Origin::signed(BOB), TOKEN_1_ID, 100 * MIL));
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_1_ID), Ok(200_000_000));
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_2_ID), Ok(502_512_563));
-		assert_eq!(Dex::get_liquidity(ALICE), 1100 * MIL);
-		assert_ok!(Dex::withdraw(Origin::signed(ALICE), 90));
-		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_1_ID), Ok(1080_000_000));
+		assert_ok!(Dex::buy_token(Origin::signed(BOB), POOL_ID, TOKEN_1_ID, 100 * MIL));
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_1_ID), Ok(200_000_000));
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_2_ID), Ok(502_512_563));
+		assert_eq!(Dex::get_liquidity(POOL_ID, ALICE), 1100 * MIL);
+		assert_ok!(Dex::withdraw(Origin::signed(ALICE), POOL_ID, 90));
+		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_1_ID), Ok(1079_100_000));
 		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_2_ID), Ok(452_261_307));
-		assert_eq!(Dex::get_liquidity(ALICE), 110_000_000);
+		assert_eq!(Dex::get_liquidity(POOL_ID, ALICE), 110_000_000);
 	});
 }
 
@@ -223,20 +885,23 @@ fn withdraw_should_work_2() {
 fn withdraw_single_token_should_work_1() {
 	new_test_ext().execute_with(|| {
 		init_tokens(1000);
-		approve(ALICE, POOL);
+		approve(ALICE, pool());
 		init_dex(100, 100);
-		assert_eq!(Dex::get_liquidity(ALICE), 200 * MIL);
+		assert_eq!(Dex::get_liquidity(POOL_ID, ALICE), 200 * MIL);
 		// withdraw 50% of token_1 and buy token_1 for 50% of token_2 share
-		assert_ok!(Dex::withdraw_single_token(Origin::signed(ALICE), TOKEN_1_ID, 50)); // 50 + 0.99 * 50 * 50 / (100 + 0.99 * 50) = 50 + 16.555183
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_1_ID), Ok(334_448_17));
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_2_ID), Ok(100_000_000));
+		assert_ok!(Dex::withdraw_single_token(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, 50)); // 50 + 0.99 * 50 * 50 / (100 + 0.99 * 50) = 50 + 16.555183
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_1_ID), Ok(334_448_17));
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_2_ID), Ok(100_000_000));
 		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_1_ID), Ok(966_555_183));
 		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_2_ID), Ok(900_000_000));
-		assert_eq!(Dex::get_liquidity(ALICE), 100_000_000);
-		assert_ok!(Dex::withdraw(Origin::signed(ALICE), 100));
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_1_ID), Ok(0)); // 33.444817
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_2_ID), Ok(0));
+		assert_eq!(Dex::get_liquidity(POOL_ID, ALICE), 100_000_000);
+		assert_ok!(Dex::withdraw(Origin::signed(ALICE), POOL_ID, 100));
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_1_ID), Ok(0)); // 33.444817
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_2_ID), Ok(500_000)); // fee of the swap
 		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_1_ID), Ok(1000_000_000));
+		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_2_ID), Ok(999_500_000));
+		assert_ok!(Dex::claim_fees(Origin::signed(ALICE), POOL_ID));
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_2_ID), Ok(0));
 		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_2_ID), Ok(1000_000_000));
 	});
 }
@@ -246,25 +911,25 @@ fn withdraw_single_token_should_work_2() {
 	new_test_ext().execute_with(|| {
 		init_tokens(200);
 		approve_and_transfer(ALICE, BOB, 100);
-		approve(ALICE, POOL);
-		approve(BOB, POOL);
+		approve(ALICE, pool());
+		approve(BOB, pool());
 		init_dex(100, 100);
-		assert_ok!(Dex::deposit(Origin::signed(BOB), TOKEN_1_ID, 100 * MIL));
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_1_ID), Ok(200_000_000));
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_2_ID), Ok(200_000_000));
-		assert_eq!(Dex::get_pool_share(&ALICE), Perbill::from_percent(50));
-		assert_eq!(Dex::get_pool_share(&BOB), Perbill::from_percent(50));
+		assert_ok!(Dex::deposit(Origin::signed(BOB), POOL_ID, TOKEN_1_ID, 100 * MIL));
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_1_ID), Ok(200_000_000));
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_2_ID), Ok(200_000_000));
+		assert_eq!(Dex::get_pool_share(POOL_ID, &ALICE), Perbill::from_percent(50));
+		assert_eq!(Dex::get_pool_share(POOL_ID, &BOB), Perbill::from_percent(50));
 		// withdraw 50% of token_1 and buy token_1 for 50% of token_2 share
-		assert_ok!(Dex::withdraw_single_token(Origin::signed(ALICE), TOKEN_1_ID, 75)); // 75 + 0.99 * 75 * 125 / (200 + 0.99 * 75) = 75 + 33.842297
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_1_ID), Ok(91_157_703)); // 91.157703
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_2_ID), Ok(200_000_000));
+		assert_ok!(Dex::withdraw_single_token(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, 75)); // 75 + 0.99 * 75 * 125 / (200 + 0.99 * 75) = 75 + 33.842297
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_1_ID), Ok(91_157_703)); // 91.157703
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_2_ID), Ok(200_000_000));
 		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_1_ID), Ok(108_842_297));
 		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_2_ID), Ok(0));
-		assert_eq!(Dex::get_pool_share(&ALICE), Perbill::from_percent(20));
-		assert_eq!(Dex::get_pool_share(&BOB), Perbill::from_percent(80));
-		assert_ok!(Dex::withdraw(Origin::signed(BOB), 100));
-		assert_ok!(Dex::buy_token(Origin::signed(BOB), TOKEN_2_ID, 50 * MIL));
-		assert_eq!(Dex::get_pool_share(&ALICE), Perbill::from_percent(100));
+		assert_eq!(Dex::get_pool_share(POOL_ID, &ALICE), Perbill::from_percent(20));
+		assert_eq!(Dex::get_pool_share(POOL_ID, &BOB), Perbill::from_percent(80));
+		assert_ok!(Dex::withdraw(Origin::signed(BOB), POOL_ID, 100));
+		assert_ok!(Dex::buy_token(Origin::signed(BOB), POOL_ID, TOKEN_2_ID, 50 * MIL));
+		assert_eq!(Dex::get_pool_share(POOL_ID, &ALICE), Perbill::from_percent(100));
 	});
 }
 
@@ -272,12 +937,18 @@ fn withdraw_single_token_should_work_2() {
 fn withdraw_should_fail() {
 	new_test_ext().execute_with(|| {
 		init_tokens(1000);
-		approve(ALICE, POOL);
+		approve(ALICE, pool());
 		init_dex(100, 1000);
-		assert_ok!(Dex::buy_token(Origin::signed(ALICE), TOKEN_1_ID, 100 * MIL)); // 100x1000 => 200x500
-		assert_eq!(Dex::get_liquidity(ALICE), 1100 * MIL);
-		assert_noop!(Dex::withdraw(Origin::signed(ALICE), 0), Error::<Test>::WrongShareValue);
-		assert_noop!(Dex::withdraw(Origin::signed(ALICE), 101), Error::<Test>::WrongShareValue);
+		assert_ok!(Dex::buy_token(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, 100 * MIL)); // 100x1000 => 200x500
+		assert_eq!(Dex::get_liquidity(POOL_ID, ALICE), 1100 * MIL);
+		assert_noop!(
+			Dex::withdraw(Origin::signed(ALICE), POOL_ID, 0),
+			Error::<Test>::WrongShareValue
+		);
+		assert_noop!(
+			Dex::withdraw(Origin::signed(ALICE), POOL_ID, 101),
+			Error::<Test>::WrongShareValue
+		);
 	});
 }
 
@@ -286,22 +957,22 @@ fn deposit_withdraw_should_work_1() {
 	new_test_ext().execute_with(|| {
 		init_tokens(1000);
 		approve_and_transfer(ALICE, BOB, 200);
-		approve(ALICE, POOL);
-		approve(BOB, POOL);
+		approve(ALICE, pool());
+		approve(BOB, pool());
 		init_dex(100, 200); // 100x200
-		assert_ok!(Dex::deposit(Origin::signed(BOB), TOKEN_1_ID, 100 * MIL));
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_1_ID), Ok(200_000_000));
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_2_ID), Ok(400_000_000));
-		assert_ok!(Dex::buy_token(Origin::signed(ALICE), TOKEN_1_ID, 100 * MIL));
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_1_ID), Ok(300_000_000));
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_2_ID), Ok(267_558_529));
+		assert_ok!(Dex::deposit(Origin::signed(BOB), POOL_ID, TOKEN_1_ID, 100 * MIL));
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_1_ID), Ok(200_000_000));
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_2_ID), Ok(400_000_000));
+		assert_ok!(Dex::buy_token(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, 100 * MIL));
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_1_ID), Ok(300_000_000));
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_2_ID), Ok(267_558_529));
 		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_1_ID), Ok(600_000_000));
 		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_2_ID), Ok(732_441_471));
-		assert_eq!(Dex::get_liquidity(ALICE), 300_000_000);
-		assert_ok!(Dex::withdraw(Origin::signed(ALICE), 50)); // 50%
-		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_1_ID), Ok(675_000_000)); // 600 + 300 * 0.5 * 0.5 = 775
+		assert_eq!(Dex::get_liquidity(POOL_ID, ALICE), 300_000_000);
+		assert_ok!(Dex::withdraw(Origin::signed(ALICE), POOL_ID, 50)); // 50%
+		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_1_ID), Ok(674_750_000)); // 600 + 299 * 0.5 * 0.5 = 674.75
 		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_2_ID), Ok(799_331_103)); // 733.(3) + 266.(6) * 0.5 * 0.5 = 800
-		assert_eq!(Dex::get_liquidity(ALICE), 150_000_000); // ~ 300 * 0.5 = 550
+		assert_eq!(Dex::get_liquidity(POOL_ID, ALICE), 150_000_000); // ~ 300 * 0.5 = 550
 	});
 }
 
@@ -310,24 +981,24 @@ fn deposit_withdraw_should_work_2() {
 	new_test_ext().execute_with(|| {
 		init_tokens(1000);
 		approve_and_transfer(ALICE, BOB, 300);
-		approve(ALICE, POOL);
-		approve(BOB, POOL);
+		approve(ALICE, pool());
+		approve(BOB, pool());
 		init_dex(100, 200); // 100x200
-		assert_ok!(Dex::deposit(Origin::signed(BOB), TOKEN_1_ID, 100 * MIL));
-		assert_ok!(Dex::deposit(Origin::signed(BOB), TOKEN_2_ID, 50 * MIL));
-		assert_ok!(Dex::deposit(Origin::signed(ALICE), TOKEN_2_ID, 100 * MIL));
-		assert_eq!(Dex::get_liquidity(BOB), 375_000_000);
-		assert_eq!(Dex::get_liquidity(ALICE), 450_000_000);
-		assert_ok!(Dex::withdraw(Origin::signed(ALICE), 100)); // 100%
-		assert_eq!(Dex::get_liquidity(ALICE), 0);
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_1_ID), Ok(125_000_000));
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_2_ID), Ok(250_000_000));
+		assert_ok!(Dex::deposit(Origin::signed(BOB), POOL_ID, TOKEN_1_ID, 100 * MIL));
+		assert_ok!(Dex::deposit(Origin::signed(BOB), POOL_ID, TOKEN_2_ID, 50 * MIL));
+		assert_ok!(Dex::deposit(Origin::signed(ALICE), POOL_ID, TOKEN_2_ID, 100 * MIL));
+		assert_eq!(Dex::get_liquidity(POOL_ID, BOB), 375_000_000);
+		assert_eq!(Dex::get_liquidity(POOL_ID, ALICE), 450_000_000);
+		assert_ok!(Dex::withdraw(Origin::signed(ALICE), POOL_ID, 100)); // 100%
+		assert_eq!(Dex::get_liquidity(POOL_ID, ALICE), 0);
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_1_ID), Ok(125_000_000));
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_2_ID), Ok(250_000_000));
 		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_1_ID), Ok(700_000_000));
 		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_2_ID), Ok(700_000_000));
-		assert_ok!(Dex::withdraw(Origin::signed(BOB), 100)); // 100%
-		assert_eq!(Dex::get_liquidity(BOB), 0);
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_1_ID), Ok(0));
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_2_ID), Ok(0));
+		assert_ok!(Dex::withdraw(Origin::signed(BOB), POOL_ID, 100)); // 100%
+		assert_eq!(Dex::get_liquidity(POOL_ID, BOB), 0);
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_1_ID), Ok(0));
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_2_ID), Ok(0));
 		assert_eq!(PalletErc1155::balance_of(&BOB, TOKEN_1_ID), Ok(300_000_000));
 		assert_eq!(PalletErc1155::balance_of(&BOB, TOKEN_2_ID), Ok(300_000_000));
 	});
@@ -339,49 +1010,58 @@ fn general_test_1() {
 		init_tokens(3000);
 		approve_and_transfer(ALICE, BOB, 500);
 		approve_and_transfer(ALICE, CHARLIE, 500);
-		approve(ALICE, POOL);
-		approve(BOB, POOL);
-		approve(CHARLIE, POOL);
+		approve(ALICE, pool());
+		approve(BOB, pool());
+		approve(CHARLIE, pool());
 		init_dex(500, 1000); // 500x1000
-		assert_eq!(Dex::get_total_liquidity().unwrap(), 1500_000_000);
+		assert_eq!(total_liquidity(), 1500_000_000);
+
+		assert_ok!(Dex::buy_token(Origin::signed(BOB), POOL_ID, TOKEN_1_ID, 100 * MIL)); //600x834.724541
+		assert_eq!(total_liquidity(), 1500_0000_00);
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_1_ID), Ok(600_000_000));
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_2_ID), Ok(834_724_541));
 
-		assert_ok!(Dex::buy_token(Origin::signed(BOB), TOKEN_1_ID, 100 * MIL)); //600x834.724541
-		assert_eq!(Dex::get_total_liquidity().unwrap(), 1500_0000_00);
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_1_ID), Ok(600_000_000));
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_2_ID), Ok(834_724_541));
+		assert_ok!(Dex::deposit(Origin::signed(BOB), POOL_ID, TOKEN_2_ID, 50 * MIL));
+		assert_eq!(total_liquidity(), 1585_880_099);
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_1_ID), Ok(635_880_099));
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_2_ID), Ok(884_724_541));
 
-		assert_ok!(Dex::deposit(Origin::signed(BOB), TOKEN_2_ID, 50 * MIL));
-		assert_eq!(Dex::get_total_liquidity().unwrap(), 1585_939_999);
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_1_ID), Ok(635_939_999));
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_2_ID), Ok(884_724_541));
+		assert_ok!(Dex::deposit(Origin::signed(CHARLIE), POOL_ID, TOKEN_2_ID, 250 * MIL));
+		assert_eq!(total_liquidity(), 2015_280_598);
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_1_ID), Ok(815_280_598));
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_2_ID), Ok(1134_724_541));
 
-		assert_ok!(Dex::deposit(Origin::signed(CHARLIE), TOKEN_2_ID, 250 * MIL));
-		assert_eq!(Dex::get_total_liquidity().unwrap(), 2015_639_998);
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_1_ID), Ok(815_639_998));
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_2_ID), Ok(1134_724_541));
+		assert_ok!(Dex::withdraw(Origin::signed(ALICE), POOL_ID, 50));
+		assert_eq!(total_liquidity(), 1265_280_598);
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_1_ID), Ok(512_240_689));
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_2_ID), Ok(712_429_300));
 
-		assert_ok!(Dex::withdraw(Origin::signed(ALICE), 50));
-		assert_eq!(Dex::get_total_liquidity().unwrap(), 1265_639_999);
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_1_ID), Ok(512_148_304));
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_2_ID), Ok(712_504_598));
+		assert_ok!(Dex::buy_token(Origin::signed(ALICE), POOL_ID, TOKEN_1_ID, 500 * MIL));
+		assert_eq!(total_liquidity(), 1265_280_598);
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_1_ID), Ok(1012_240_689));
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_2_ID), Ok(361_963_942));
 
-		assert_ok!(Dex::buy_token(Origin::signed(ALICE), TOKEN_1_ID, 500 * MIL));
-		assert_eq!(Dex::get_total_liquidity().unwrap(), 1265_639_999);
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_1_ID), Ok(1012_148_304));
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_2_ID), Ok(362_318_062));
+		assert_ok!(Dex::withdraw(Origin::signed(CHARLIE), POOL_ID, 100));
+		assert_ok!(Dex::withdraw(Origin::signed(BOB), POOL_ID, 100));
+		assert_eq!(total_liquidity(), 750_000_002);
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_1_ID), Ok(602_453_087));
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_2_ID), Ok(214_555_537));
 
-		assert_ok!(Dex::withdraw(Origin::signed(CHARLIE), 100));
-		assert_ok!(Dex::withdraw(Origin::signed(BOB), 100));
-		assert_eq!(Dex::get_total_liquidity().unwrap(), 750_000_002);
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_1_ID), Ok(599_784_482));
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_2_ID), Ok(214_704_456));
+		assert_ok!(Dex::withdraw(Origin::signed(ALICE), POOL_ID, 100));
+		assert_eq!(total_liquidity(), 2); // 0.000002
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_1_ID), Ok(6_000_002)); // unclaimed fees
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_2_ID), Ok(1));
+		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_1_ID), Ok(1899_492_994));
+		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_2_ID), Ok(1987_316_135));
 
-		assert_ok!(Dex::withdraw(Origin::signed(ALICE), 100));
-		assert_eq!(Dex::get_total_liquidity().unwrap(), 2); // 0.000002
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_1_ID), Ok(1));
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_2_ID), Ok(0));
-		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_1_ID), Ok(1903_276_175));
-		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_2_ID), Ok(1987_110_935));
+		assert_eq!(Dex::get_pending_fees(POOL_ID, &ALICE), (3_963_768, 0));
+		assert_eq!(Dex::get_pending_fees(POOL_ID, &BOB), (339_371, 0));
+		assert_eq!(Dex::get_pending_fees(POOL_ID, &CHARLIE), (1_696_858, 0));
+		assert_ok!(Dex::claim_fees(Origin::signed(ALICE), POOL_ID));
+		assert_ok!(Dex::claim_fees(Origin::signed(BOB), POOL_ID));
+		assert_ok!(Dex::claim_fees(Origin::signed(CHARLIE), POOL_ID));
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_1_ID), Ok(5));
+		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_1_ID), Ok(1903_456_762));
 	});
 }
 
@@ -393,34 +1073,289 @@ fn incentivize_model_test() {
 		approve_and_transfer(ALICE, CHARLIE, 1000);
 		approve_and_transfer(ALICE, MARRY, 1000);
 		approve_and_transfer(ALICE, JOHN, 1000);
-		approve(ALICE, POOL);
-		approve(BOB, POOL);
-		approve(CHARLIE, POOL);
-		approve(MARRY, POOL);
-		approve(JOHN, POOL);
+		approve(ALICE, pool());
+		approve(BOB, pool());
+		approve(CHARLIE, pool());
+		approve(MARRY, pool());
+		approve(JOHN, pool());
 		init_dex(1000, 1000);
-		assert_ok!(Dex::deposit(Origin::signed(BOB), TOKEN_1_ID, 500 * MIL));
-
-		assert_ok!(Dex::buy_token(Origin::signed(CHARLIE), TOKEN_1_ID, 250 * MIL));
-		assert_ok!(Dex::buy_token(Origin::signed(MARRY), TOKEN_2_ID, 400 * MIL));
-		assert_ok!(Dex::buy_token(Origin::signed(JOHN), TOKEN_1_ID, 100 * MIL));
-		assert_ok!(Dex::buy_token(Origin::signed(CHARLIE), TOKEN_2_ID, 100 * MIL));
-		assert_ok!(Dex::buy_token(Origin::signed(JOHN), TOKEN_2_ID, 500 * MIL));
-		assert_ok!(Dex::buy_token(Origin::signed(MARRY), TOKEN_1_ID, 500 * MIL));
-
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_1_ID), Ok(1543_933_772));
-		assert_eq!(PalletErc1155::balance_of(&POOL, TOKEN_2_ID), Ok(1472_913_599));
-
-		assert_eq!(Dex::get_total_reward(), Ok(16_847_371)); // 16.847371
-		assert_eq!(Dex::get_liquidity(ALICE), 2000_000_000);
-		assert_ok!(Dex::withdraw(Origin::signed(ALICE), 100));
-		// ALICE gained 11.231578 tokens as reward
-		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_1_ID), Ok(1529_289_180));
-		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_2_ID), Ok(1481_942_398));
-		assert_eq!(Dex::get_liquidity(BOB), 1000_000_000);
-		assert_ok!(Dex::withdraw(Origin::signed(BOB), 100));
-		// BOB gained 5.615793 tokens as reward
-		assert_eq!(PalletErc1155::balance_of(&BOB, TOKEN_1_ID), Ok(514_644_591));
-		assert_eq!(PalletErc1155::balance_of(&BOB, TOKEN_2_ID), Ok(490_971_200));
+		assert_ok!(Dex::deposit(Origin::signed(BOB), POOL_ID, TOKEN_1_ID, 500 * MIL));
+
+		assert_ok!(Dex::buy_token(Origin::signed(CHARLIE), POOL_ID, TOKEN_1_ID, 250 * MIL));
+		assert_ok!(Dex::buy_token(Origin::signed(MARRY), POOL_ID, TOKEN_2_ID, 400 * MIL));
+		assert_ok!(Dex::buy_token(Origin::signed(JOHN), POOL_ID, TOKEN_1_ID, 100 * MIL));
+		assert_ok!(Dex::buy_token(Origin::signed(CHARLIE), POOL_ID, TOKEN_2_ID, 100 * MIL));
+		assert_ok!(Dex::buy_token(Origin::signed(JOHN), POOL_ID, TOKEN_2_ID, 500 * MIL));
+		assert_ok!(Dex::buy_token(Origin::signed(MARRY), POOL_ID, TOKEN_1_ID, 500 * MIL));
+
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_1_ID), Ok(1544_471_163));
+		assert_eq!(PalletErc1155::balance_of(&pool(), TOKEN_2_ID), Ok(1474_871_256));
+
+		// 1% of 850 token_1 and 1000 token_2 traded
+		assert_eq!(Dex::get_collected_fees(POOL_ID, TOKEN_1_ID), 8_500_000);
+		assert_eq!(Dex::get_collected_fees(POOL_ID, TOKEN_2_ID), 10_000_000);
+		// ALICE owns 2/3 of the pool, BOB 1/3
+		assert_eq!(Dex::get_pending_fees(POOL_ID, &ALICE), (5_666_666, 6_666_666));
+		assert_eq!(Dex::get_pending_fees(POOL_ID, &BOB), (2_833_333, 3_333_333));
+
+		assert_ok!(Dex::claim_fees(Origin::signed(ALICE), POOL_ID));
+		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_1_ID), Ok(505_666_666));
+		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_2_ID), Ok(506_666_666));
+		assert_eq!(Dex::get_liquidity(POOL_ID, ALICE), 2000_000_000);
+		assert_eq!(Dex::get_pending_fees(POOL_ID, &ALICE), (0, 0));
+		assert_noop!(Dex::claim_fees(Origin::signed(ALICE), POOL_ID), Error::<Test>::NoFeesToClaim);
+		assert_ok!(Dex::withdraw(Origin::signed(ALICE), POOL_ID, 100));
+		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_1_ID), Ok(1529_647_440));
+		assert_eq!(PalletErc1155::balance_of(&ALICE, TOKEN_2_ID), Ok(1483_247_502));
+
+		// fees are kept for BOB after the withdrawal
+		assert_eq!(Dex::get_liquidity(POOL_ID, BOB), 1000_000_000);
+		assert_ok!(Dex::withdraw(Origin::signed(BOB), POOL_ID, 100));
+		assert_eq!(PalletErc1155::balance_of(&BOB, TOKEN_1_ID), Ok(511_990_388));
+		assert_eq!(PalletErc1155::balance_of(&BOB, TOKEN_2_ID), Ok(488_290_419));
+		assert_eq!(Dex::get_pending_fees(POOL_ID, &BOB), (2_833_333, 3_333_333));
+		assert_ok!(Dex::claim_fees(Origin::signed(BOB), POOL_ID));
+		assert_eq!(PalletErc1155::balance_of(&BOB, TOKEN_1_ID), Ok(514_823_721));
+		assert_eq!(PalletErc1155::balance_of(&BOB, TOKEN_2_ID), Ok(491_623_752));
+	});
+}
+
+#[test]
+fn liquidity_mining_should_work() {
+	new_test_ext().execute_with(|| {
+		init_tokens(1000);
+		approve_and_transfer(ALICE, BOB, 100);
+		approve(ALICE, pool());
+		approve(BOB, pool());
+		init_dex(100, 100);
+		init_farming(10, 1, 11, 100); // 10 tokens per block for blocks 1..11
+
+		System::set_block_number(1);
+		assert_ok!(Dex::stake(Origin::signed(ALICE), POOL_ID, 100 * MIL));
+		System::set_block_number(3);
+		assert_ok!(Dex::deposit(Origin::signed(BOB), POOL_ID, TOKEN_1_ID, 50 * MIL));
+		assert_ok!(Dex::stake(Origin::signed(BOB), POOL_ID, 100 * MIL));
+		assert_eq!(Dex::get_total_staked(POOL_ID), 200 * MIL);
+		assert_eq!(Dex::get_pending_reward(POOL_ID, &ALICE), 20 * MIL); // 2 blocks alone
+
+		System::set_block_number(5);
+		assert_eq!(Dex::get_pending_reward(POOL_ID, &ALICE), 30 * MIL); // + 2 blocks * 50%
+		assert_eq!(Dex::get_pending_reward(POOL_ID, &BOB), 10 * MIL);
+		assert_ok!(Dex::claim(Origin::signed(BOB), POOL_ID));
+		assert_eq!(PalletErc1155::balance_of(&BOB, REWARD_TOKEN_ID), Ok(10 * MIL));
+		assert_eq!(Dex::get_pending_reward(POOL_ID, &BOB), 0);
+
+		// emission stops at the end of the schedule
+		System::set_block_number(20);
+		assert_eq!(Dex::get_pending_reward(POOL_ID, &ALICE), 60 * MIL);
+		assert_eq!(Dex::get_pending_reward(POOL_ID, &BOB), 30 * MIL);
+		assert_ok!(Dex::claim(Origin::signed(ALICE), POOL_ID));
+		assert_ok!(Dex::claim(Origin::signed(BOB), POOL_ID));
+		assert_eq!(PalletErc1155::balance_of(&ALICE, REWARD_TOKEN_ID), Ok(60 * MIL));
+		assert_eq!(PalletErc1155::balance_of(&BOB, REWARD_TOKEN_ID), Ok(40 * MIL));
+		assert_eq!(PalletErc1155::balance_of(&Dex::reward_account(), REWARD_TOKEN_ID), Ok(0));
+	});
+}
+
+#[test]
+fn staked_liquidity_should_be_locked() {
+	new_test_ext().execute_with(|| {
+		init_tokens(1000);
+		approve(ALICE, pool());
+		init_dex(100, 100);
+		assert_ok!(Dex::stake(Origin::signed(ALICE), POOL_ID, 150 * MIL));
+		assert_noop!(
+			Dex::withdraw(Origin::signed(ALICE), POOL_ID, 50),
+			Error::<Test>::LiquidityIsStaked
+		);
+		assert_noop!(
+			Dex::stake(Origin::signed(ALICE), POOL_ID, 100 * MIL),
+			Error::<Test>::NotEnoughLiquidityToStake
+		);
+		assert_noop!(
+			Dex::unstake(Origin::signed(ALICE), POOL_ID, 151 * MIL),
+			Error::<Test>::NotEnoughStaked
+		);
+		assert_ok!(Dex::unstake(Origin::signed(ALICE), POOL_ID, 100 * MIL));
+		assert_ok!(Dex::withdraw(Origin::signed(ALICE), POOL_ID, 50));
+		assert_eq!(Dex::get_liquidity(POOL_ID, ALICE), 100 * MIL);
+		assert_eq!(Dex::get_stake(POOL_ID, ALICE).amount, 50 * MIL);
+	});
+}
+
+#[test]
+fn claim_should_fail() {
+	new_test_ext().execute_with(|| {
+		init_tokens(1000);
+		approve(ALICE, pool());
+		init_dex(100, 100);
+		assert_noop!(Dex::claim(Origin::signed(ALICE), POOL_ID), Error::<Test>::NoRewardSchedule);
+		assert_ok!(PalletErc1155::init(
+			Origin::signed(ALICE),
+			vec![REWARD_TOKEN_ID],
+			vec![100 * MIL],
+			vec![6]
+		));
+		assert_ok!(Dex::set_reward_schedule(Origin::root(), POOL_ID, REWARD_TOKEN_ID, MIL, 1, 11));
+		assert_noop!(Dex::claim(Origin::signed(ALICE), POOL_ID), Error::<Test>::NoRewardToClaim);
+		System::set_block_number(1);
+		assert_ok!(Dex::stake(Origin::signed(ALICE), POOL_ID, 100 * MIL));
+		System::set_block_number(2);
+		// reward account was never funded
+		assert_noop!(
+			Dex::claim(Origin::signed(ALICE), POOL_ID),
+			pallet_erc1155::Error::<Test>::InsufficientFunds
+		);
+	});
+}
+
+#[test]
+fn set_reward_schedule_should_fail() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Dex::set_reward_schedule(Origin::signed(ALICE), POOL_ID, REWARD_TOKEN_ID, MIL, 1, 11),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Dex::set_reward_schedule(Origin::root(), POOL_ID, REWARD_TOKEN_ID, MIL, 11, 11),
+			Error::<Test>::WrongRewardSchedule
+		);
+		assert_noop!(
+			Dex::set_reward_schedule(Origin::root(), POOL_ID, REWARD_TOKEN_ID, 0, 1, 11),
+			Error::<Test>::WrongRewardSchedule
+		);
+	});
+}
+
+#[test]
+fn reward_token_should_not_change_while_staked() {
+	new_test_ext().execute_with(|| {
+		init_tokens(1000);
+		approve(ALICE, pool());
+		init_dex(100, 100);
+		init_farming(1, 1, 11, 100);
+		System::set_block_number(1);
+		assert_ok!(Dex::stake(Origin::signed(ALICE), POOL_ID, 100 * MIL));
+		assert_noop!(
+			Dex::set_reward_schedule(Origin::root(), POOL_ID, TOKEN_1_ID, MIL, 1, 21),
+			Error::<Test>::RewardTokenInUse
+		);
+		// the emission of the same token can still be changed
+		assert_ok!(Dex::set_reward_schedule(Origin::root(), POOL_ID, REWARD_TOKEN_ID, MIL, 1, 21));
+
+		System::set_block_number(3);
+		assert_ok!(Dex::unstake(Origin::signed(ALICE), POOL_ID, 100 * MIL));
+		// the rewards of the unstaked liquidity are still owed in the reward token
+		assert_noop!(
+			Dex::set_reward_schedule(Origin::root(), POOL_ID, TOKEN_1_ID, MIL, 1, 21),
+			Error::<Test>::RewardTokenInUse
+		);
+		assert_ok!(Dex::claim(Origin::signed(ALICE), POOL_ID));
+		assert_eq!(PalletErc1155::balance_of(&ALICE, REWARD_TOKEN_ID), Ok(2 * MIL));
+		assert_ok!(Dex::set_reward_schedule(Origin::root(), POOL_ID, TOKEN_1_ID, MIL, 1, 21));
+	});
+}
+
+#[test]
+fn paused_pool_should_only_allow_withdraw() {
+	new_test_ext().execute_with(|| {
+		init_tokens(1000);
+		approve_and_transfer(ALICE, BOB, 100);
+		approve(ALICE, pool());
+		approve(BOB, pool());
+		init_dex(100, 100);
+		assert_ok!(Dex::deposit(Origin::signed(BOB), POOL_ID, TOKEN_1_ID, 50 * MIL));
+		assert_ok!(Dex::pause_pool(Origin::root(), POOL_ID));
+		assert!(Dex::is_paused(POOL_ID));
+		assert_noop!(
+			Dex::buy_token(Origin::signed(BOB), POOL_ID, TOKEN_1_ID, 10 * MIL),
+			Error::<Test>::Paused
+		);
+		assert_noop!(
+			Dex::deposit(Origin::signed(BOB), POOL_ID, TOKEN_1_ID, 10 * MIL),
+			Error::<Test>::Paused
+		);
+		assert_noop!(
+			Dex::deposit_single_token(Origin::signed(BOB), POOL_ID, TOKEN_1_ID, 10 * MIL),
+			Error::<Test>::Paused
+		);
+		assert_noop!(
+			Dex::withdraw_single_token(Origin::signed(BOB), POOL_ID, TOKEN_1_ID, 100),
+			Error::<Test>::Paused
+		);
+		assert_ok!(Dex::withdraw(Origin::signed(BOB), POOL_ID, 100));
+		assert_eq!(PalletErc1155::balance_of(&BOB, TOKEN_1_ID), Ok(100 * MIL));
+		assert_eq!(PalletErc1155::balance_of(&BOB, TOKEN_2_ID), Ok(100 * MIL));
+
+		assert_ok!(Dex::unpause_pool(Origin::root(), POOL_ID));
+		assert_ok!(Dex::buy_token(Origin::signed(BOB), POOL_ID, TOKEN_1_ID, 10 * MIL));
+	});
+}
+
+#[test]
+fn pause_pool_should_fail() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Dex::pause_pool(Origin::root(), POOL_ID), Error::<Test>::Uninitilized);
+		init_tokens(1000);
+		approve(ALICE, pool());
+		init_dex(100, 100);
+		assert_noop!(Dex::pause_pool(Origin::signed(ALICE), POOL_ID), DispatchError::BadOrigin);
+		assert_noop!(Dex::unpause_pool(Origin::root(), POOL_ID), Error::<Test>::NotPaused);
+		assert_ok!(Dex::pause_pool(Origin::root(), POOL_ID));
+		assert_noop!(Dex::pause_pool(Origin::root(), POOL_ID), Error::<Test>::AlreadyPaused);
+		assert_noop!(Dex::unpause_pool(Origin::signed(ALICE), POOL_ID), DispatchError::BadOrigin);
+	});
+}
+
+#[test]
+fn migration_to_multi_pool_should_work() {
+	use crate::migrations::v1::MigrateToMultiPool;
+	use codec::Encode;
+	use frame_support::{
+		storage::migration::put_storage_value,
+		traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+		Blake2_128Concat, StorageHasher,
+	};
+
+	const OLD_POOL: u64 = 101;
+
+	new_test_ext().execute_with(|| {
+		init_tokens(1000);
+		approve_and_transfer(ALICE, OLD_POOL, 100);
+		approve_and_transfer(ALICE, BOB, 100);
+		approve(BOB, OLD_POOL);
+
+		// the single pool layout, ALICE and BOB share the pool
+		StorageVersion::new(0).put::<Dex>();
+		put_storage_value(b"Dex", b"PoolAddress", &[], OLD_POOL);
+		put_storage_value(b"Dex", b"TokenIds", &[], (TOKEN_1_ID, TOKEN_2_ID));
+		put_storage_value(b"Dex", b"TotalLiquidity", &[], 200 * MIL);
+		for (owner, liquidity) in [(ALICE, 150 * MIL), (BOB, 50 * MIL)] {
+			let key = Blake2_128Concat::hash(&owner.encode());
+			put_storage_value(b"Dex", b"Liquidity", &key, liquidity);
+		}
+
+		MigrateToMultiPool::<Test>::on_runtime_upgrade();
+
+		assert_eq!(Dex::on_chain_storage_version(), 1);
+		let pool_info = Dex::get_pool(POOL_ID).unwrap();
+		assert_eq!(pool_info.account, OLD_POOL);
+		assert_eq!(pool_info.token_ids, (TOKEN_1_ID, TOKEN_2_ID));
+		assert_eq!(pool_info.total_liquidity, 200 * MIL);
+		assert_eq!(Dex::get_pool_id(TOKEN_2_ID, TOKEN_1_ID), Some(POOL_ID));
+		assert_eq!(Dex::get_next_pool_id(), 1);
+		assert_eq!(Dex::get_liquidity(POOL_ID, ALICE), 150 * MIL);
+		assert_eq!(Dex::get_liquidity(POOL_ID, BOB), 50 * MIL);
+		assert_eq!(PalletErc1155::balance_of(&OLD_POOL, TOKEN_1_ID), Ok(100 * MIL));
+		assert_eq!(PalletErc1155::balance_of(&OLD_POOL, TOKEN_2_ID), Ok(100 * MIL));
+
+		// the migrated pool keeps working with its reserves
+		assert_ok!(Dex::withdraw(Origin::signed(ALICE), POOL_ID, 100));
+		assert_eq!(PalletErc1155::balance_of(&OLD_POOL, TOKEN_1_ID), Ok(25_000_000));
+		assert_eq!(PalletErc1155::balance_of(&OLD_POOL, TOKEN_2_ID), Ok(25_000_000));
+
+		// running it again is a no-op
+		MigrateToMultiPool::<Test>::on_runtime_upgrade();
+		assert_eq!(Dex::get_liquidity(POOL_ID, BOB), 50 * MIL);
 	});
 }
@@ -0,0 +1,35 @@
+//! Runtime API of the ERC1155 pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	pub trait Erc1155Api<AccountId, TokenId, Balance, Metadata> where
+		AccountId: Codec,
+		TokenId: Codec,
+		Balance: Codec,
+		Metadata: Codec,
+	{
+		/// Metadata of `token_id`, `None` if the token is not initialized.
+		fn metadata(token_id: TokenId) -> Option<Metadata>;
+
+		/// URI of the metadata of `token_id`, clients replace `{id}` by the hexadecimal token id.
+		fn uri(token_id: TokenId) -> Vec<u8>;
+
+		/// Up to `limit` of the tokens `account` holds as `(token_id, balance)`, skipping the
+		/// first `offset`.
+		fn tokens_of(account: AccountId, offset: u32, limit: u32) -> Vec<(TokenId, Balance)>;
+
+		/// Up to `limit` of the holders of `token_id` as `(account, balance)`, skipping the first
+		/// `offset`.
+		fn holders_of(token_id: TokenId, offset: u32, limit: u32) -> Vec<(AccountId, Balance)>;
+
+		/// Owner of the non-fungible `token_id`, `None` if it is not one or was burned.
+		fn owner_of(token_id: TokenId) -> Option<AccountId>;
+
+		/// Recipient and amount of the royalty owed when `token_id` is sold for `sale_price`.
+		fn royalty_info(token_id: TokenId, sale_price: Balance) -> Option<(AccountId, Balance)>;
+	}
+}
@@ -0,0 +1,203 @@
+//! Exposes the native currency through the `Erc1155` trait.
+
+use frame_support::{
+	ensure,
+	traits::{Currency, ExistenceRequirement, Get},
+	transactional,
+};
+use sp_runtime::{
+	traits::{Bounded, Zero},
+	DispatchError, DispatchResult,
+};
+use sp_std::{marker::PhantomData, vec::Vec};
+use traits::Erc1155;
+
+/// Implements `Erc1155` over `NativeCurrency` for the `NativeTokenId` token and over `Tokens` for
+/// the other tokens, so that native balances can be used wherever ERC1155 tokens are, e.g. in
+/// the pools of `pallet_dex`.
+///
+/// `NativeTokenId` has to be the `NativeTokenId` of `Tokens`, which keeps it from being
+/// initialized. The native token can not be minted or burned through the adapter. Its
+/// `transfer_from_*` need `to` to be an operator of `from` approved for all tokens, and every
+/// native transfer keeps `from` alive.
+pub struct NativeAdapter<NativeCurrency, Tokens, NativeTokenId>(
+	PhantomData<(NativeCurrency, Tokens, NativeTokenId)>,
+);
+
+impl<AccountId, NativeCurrency, Tokens, NativeTokenId> Erc1155<AccountId>
+	for NativeAdapter<NativeCurrency, Tokens, NativeTokenId>
+where
+	NativeCurrency: Currency<AccountId, Balance = Tokens::Balance>,
+	Tokens: Erc1155<AccountId>,
+	NativeTokenId: Get<Tokens::TokenId>,
+{
+	type TokenId = Tokens::TokenId;
+	type Balance = Tokens::Balance;
+
+	fn init(
+		who: &AccountId,
+		token_ids: &Vec<Self::TokenId>,
+		initial_supplies: &Vec<Self::Balance>,
+		decimals: &Vec<u8>,
+	) -> DispatchResult {
+		ensure!(
+			!token_ids.contains(&NativeTokenId::get()),
+			DispatchError::Other("the native token can not be initialized")
+		);
+		Tokens::init(who, token_ids, initial_supplies, decimals)
+	}
+
+	fn total_supply(token_id: Self::TokenId) -> Result<Self::Balance, DispatchError> {
+		if token_id == NativeTokenId::get() {
+			Ok(NativeCurrency::total_issuance())
+		} else {
+			Tokens::total_supply(token_id)
+		}
+	}
+
+	fn balance_of(
+		account: &AccountId,
+		token_id: Self::TokenId,
+	) -> Result<Self::Balance, DispatchError> {
+		if token_id == NativeTokenId::get() {
+			Ok(NativeCurrency::free_balance(account))
+		} else {
+			Tokens::balance_of(account, token_id)
+		}
+	}
+
+	fn balance_of_batch(
+		accounts: Vec<&AccountId>,
+		token_ids: Vec<Self::TokenId>,
+	) -> Result<Vec<Self::Balance>, DispatchError> {
+		ensure!(
+			accounts.len() == token_ids.len(),
+			DispatchError::Other("accounts and token ids differ in length")
+		);
+		accounts
+			.into_iter()
+			.zip(token_ids)
+			.map(|(account, id)| Self::balance_of(account, id))
+			.collect()
+	}
+
+	fn transfer_from_single(
+		from: &AccountId,
+		to: &AccountId,
+		token_id: &Self::TokenId,
+		amount: &Self::Balance,
+		data: &[u8],
+	) -> DispatchResult {
+		if *token_id == NativeTokenId::get() {
+			Self::ensure_native_approved(from, to)?;
+			NativeCurrency::transfer(from, to, *amount, ExistenceRequirement::KeepAlive)
+		} else {
+			Tokens::transfer_from_single(from, to, token_id, amount, data)
+		}
+	}
+
+	#[transactional]
+	fn transfer_from_batch(
+		from: &AccountId,
+		to: &AccountId,
+		token_ids: &Vec<Self::TokenId>,
+		amounts: &Vec<Self::Balance>,
+		data: &[u8],
+	) -> DispatchResult {
+		ensure!(
+			token_ids.len() == amounts.len(),
+			DispatchError::Other("token ids and amounts differ in length")
+		);
+		let (native, tokens): (Vec<_>, Vec<_>) = token_ids
+			.iter()
+			.copied()
+			.zip(amounts.iter().copied())
+			.partition(|(id, _)| *id == NativeTokenId::get());
+		if !native.is_empty() {
+			Self::ensure_native_approved(from, to)?;
+		}
+		for (_, amount) in native {
+			NativeCurrency::transfer(from, to, amount, ExistenceRequirement::KeepAlive)?;
+		}
+		if !tokens.is_empty() {
+			let (token_ids, amounts): (Vec<_>, Vec<_>) = tokens.into_iter().unzip();
+			Tokens::transfer_from_batch(from, to, &token_ids, &amounts, data)?;
+		}
+		Ok(())
+	}
+
+	fn transfer(
+		from: &AccountId,
+		to: &AccountId,
+		token_id: Self::TokenId,
+		amount: Self::Balance,
+		data: &[u8],
+	) -> DispatchResult {
+		if token_id == NativeTokenId::get() {
+			NativeCurrency::transfer(from, to, amount, ExistenceRequirement::KeepAlive)
+		} else {
+			Tokens::transfer(from, to, token_id, amount, data)
+		}
+	}
+
+	fn mint(to: &AccountId, token_id: Self::TokenId, amount: Self::Balance) -> DispatchResult {
+		ensure!(
+			token_id != NativeTokenId::get(),
+			DispatchError::Other("the native token can not be minted")
+		);
+		Tokens::mint(to, token_id, amount)
+	}
+
+	fn burn(from: &AccountId, token_id: Self::TokenId, amount: Self::Balance) -> DispatchResult {
+		ensure!(
+			token_id != NativeTokenId::get(),
+			DispatchError::Other("the native token can not be burned")
+		);
+		Tokens::burn(from, token_id, amount)
+	}
+
+	fn set_approval_for_all(owner: &AccountId, operator: &AccountId, approved: bool) {
+		Tokens::set_approval_for_all(owner, operator, approved)
+	}
+
+	fn is_approved_for_all(owner: &AccountId, operator: &AccountId) -> bool {
+		Tokens::is_approved_for_all(owner, operator)
+	}
+
+	fn allowance(owner: &AccountId, spender: &AccountId, token_id: Self::TokenId) -> Self::Balance {
+		if token_id == NativeTokenId::get() {
+			// native transfers are only approved for all tokens, never per token
+			if Tokens::is_approved_for_all(owner, spender) {
+				Self::Balance::max_value()
+			} else {
+				Zero::zero()
+			}
+		} else {
+			Tokens::allowance(owner, spender, token_id)
+		}
+	}
+
+	fn royalty_info(
+		token_id: Self::TokenId,
+		sale_price: Self::Balance,
+	) -> Option<(AccountId, Self::Balance)> {
+		if token_id == NativeTokenId::get() {
+			None
+		} else {
+			Tokens::royalty_info(token_id, sale_price)
+		}
+	}
+}
+
+impl<NativeCurrency, Tokens, NativeTokenId> NativeAdapter<NativeCurrency, Tokens, NativeTokenId> {
+	fn ensure_native_approved<AccountId>(from: &AccountId, to: &AccountId) -> DispatchResult
+	where
+		Tokens: Erc1155<AccountId>,
+	{
+		ensure!(
+			Tokens::is_approved_for_all(from, to),
+			DispatchError::Other("the native token transfer is not approved")
+		);
+		Ok(())
+	}
+}
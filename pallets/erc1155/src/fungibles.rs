@@ -0,0 +1,155 @@
+//! Exposes the tokens of the pallet through the FRAME `fungibles` traits.
+
+use crate::pallet::{Config, Error, Pallet, TotalSupply};
+use frame_support::{
+	ensure,
+	traits::{
+		tokens::{
+			fungibles::{Inspect, Mutate, Transfer, Unbalanced},
+			DepositConsequence, WithdrawConsequence,
+		},
+		Get,
+	},
+};
+use sp_runtime::{
+	traits::{CheckedAdd, CheckedSub, One, Zero},
+	DispatchError, DispatchResult,
+};
+use traits::Erc1155;
+
+/// Balances have no existential deposit, so `minimum_balance` is zero and accounts never die.
+/// Frozen balances can not be withdrawn. The wrapped native currency and the non-fungible tokens
+/// are unknown to deposits, as `mint_into` rejects them, and the wrapped native currency is
+/// unknown to withdrawals too, so none of it is reducible.
+impl<T: Config> Inspect<T::AccountId> for Pallet<T> {
+	type AssetId = T::TokenId;
+	type Balance = T::Balance;
+
+	fn total_issuance(asset: T::TokenId) -> T::Balance {
+		TotalSupply::<T>::get(asset)
+	}
+
+	fn minimum_balance(_asset: T::TokenId) -> T::Balance {
+		Zero::zero()
+	}
+
+	fn balance(asset: T::TokenId, who: &T::AccountId) -> T::Balance {
+		Self::get_balance(who, asset)
+	}
+
+	fn reducible_balance(asset: T::TokenId, who: &T::AccountId, _keep_alive: bool) -> T::Balance {
+		if asset == T::WrappedTokenId::get() ||
+			Self::is_token_frozen(asset) ||
+			Self::is_frozen(asset, who)
+		{
+			return Zero::zero()
+		}
+		Self::get_balance(who, asset)
+	}
+
+	fn can_deposit(
+		asset: T::TokenId,
+		who: &T::AccountId,
+		amount: T::Balance,
+	) -> DepositConsequence {
+		if !Self::is_initialized(&asset) ||
+			asset == T::WrappedTokenId::get() ||
+			Self::is_non_fungible(asset)
+		{
+			return DepositConsequence::UnknownAsset
+		}
+		if TotalSupply::<T>::get(asset).checked_add(&amount).is_none() ||
+			Self::get_balance(who, asset).checked_add(&amount).is_none()
+		{
+			return DepositConsequence::Overflow
+		}
+		DepositConsequence::Success
+	}
+
+	fn can_withdraw(
+		asset: T::TokenId,
+		who: &T::AccountId,
+		amount: T::Balance,
+	) -> WithdrawConsequence<T::Balance> {
+		if !Self::is_initialized(&asset) || asset == T::WrappedTokenId::get() {
+			return WithdrawConsequence::UnknownAsset
+		}
+		if amount.is_zero() {
+			return WithdrawConsequence::Success
+		}
+		if Self::is_token_frozen(asset) || Self::is_frozen(asset, who) {
+			return WithdrawConsequence::Frozen
+		}
+		if Self::get_balance(who, asset).checked_sub(&amount).is_none() {
+			return WithdrawConsequence::NoFunds
+		}
+		WithdrawConsequence::Success
+	}
+}
+
+/// Mints and burns like `Erc1155::mint` and `Erc1155::burn`, so the wrapped native currency and
+/// the non-fungible tokens can not be minted. Zero amounts are accepted and change nothing.
+impl<T: Config> Mutate<T::AccountId> for Pallet<T> {
+	fn mint_into(asset: T::TokenId, who: &T::AccountId, amount: T::Balance) -> DispatchResult {
+		if amount.is_zero() {
+			return Ok(())
+		}
+		<Self as Erc1155<_>>::mint(who, asset, amount)
+	}
+
+	fn burn_from(
+		asset: T::TokenId,
+		who: &T::AccountId,
+		amount: T::Balance,
+	) -> Result<T::Balance, DispatchError> {
+		if !amount.is_zero() {
+			<Self as Erc1155<_>>::burn(who, asset, amount)?;
+		}
+		Ok(amount)
+	}
+}
+
+/// Transfers like `Erc1155::transfer`, without approvals and without data. A transfer to the
+/// source itself changes nothing.
+impl<T: Config> Transfer<T::AccountId> for Pallet<T> {
+	fn transfer(
+		asset: T::TokenId,
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		amount: T::Balance,
+		_keep_alive: bool,
+	) -> Result<T::Balance, DispatchError> {
+		if amount.is_zero() || source == dest {
+			return Ok(amount)
+		}
+		<Self as Erc1155<_>>::transfer(source, dest, asset, amount, &[])?;
+		Ok(amount)
+	}
+}
+
+/// Lets FRAME code built on `fungibles::Balanced` settle imbalances in the tokens of the pallet.
+/// The wrapped native currency can not be changed this way, as it has to stay backed by the
+/// locked native currency, and no account can hold more than one of a non-fungible token. Frozen
+/// balances can not be lowered.
+impl<T: Config> Unbalanced<T::AccountId> for Pallet<T> {
+	fn set_balance(asset: T::TokenId, who: &T::AccountId, amount: T::Balance) -> DispatchResult {
+		ensure!(asset != T::WrappedTokenId::get(), Error::<T>::ReservedTokenId);
+		Self::token_initialized(&asset)?;
+		ensure!(
+			!Self::is_non_fungible(asset) || amount <= One::one(),
+			Error::<T>::InvalidNftAmount
+		);
+		if amount < Self::get_balance(who, asset) {
+			Self::ensure_not_frozen(who, &asset)?;
+		}
+		Self::set_balance(who, &asset, amount);
+		Ok(())
+	}
+
+	fn set_total_issuance(asset: T::TokenId, amount: T::Balance) {
+		if asset == T::WrappedTokenId::get() {
+			return
+		}
+		TotalSupply::<T>::insert(asset, amount);
+	}
+}
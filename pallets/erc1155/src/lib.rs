@@ -1,7 +1,12 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub use adapter::NativeAdapter;
 pub use pallet::*;
 
+pub mod adapter;
+pub mod fungibles;
+pub mod migrations;
+
 #[cfg(test)]
 mod mock;
 
@@ -11,15 +16,62 @@ mod tests;
 #[frame_support::pallet]
 pub mod pallet {
 	use codec::FullCodec;
-	use frame_support::{dispatch::DispatchResult, ensure, pallet_prelude::*, transactional};
+	use frame_support::{
+		dispatch::DispatchResult,
+		ensure,
+		pallet_prelude::*,
+		traits::{Currency, ExistenceRequirement},
+		transactional, PalletId,
+	};
 	use frame_system::pallet_prelude::*;
-	use sp_runtime::traits::{AtLeast32BitUnsigned, CheckedAdd, CheckedMul, CheckedSub, Zero};
+	use sp_runtime::{
+		traits::{
+			AccountIdConversion, AtLeast32BitUnsigned, CheckedAdd, CheckedSub, One,
+			SaturatedConversion, Saturating, Zero,
+		},
+		PerThing, Perbill,
+	};
 	use sp_std::vec::Vec;
 	use sp_std::{
 		cmp::{Eq, PartialEq},
 		fmt::Debug,
 	};
-	use traits::Erc1155;
+	use traits::{Erc1155, TransferHook};
+
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct TokenInfo<AccountId> {
+		/// Account which initialized the token.
+		pub creator: AccountId,
+		/// Account managing the minters of the token, the zero account for tokens without one.
+		pub admin: AccountId,
+	}
+
+	/// Metadata shown by wallets, an empty `uri` falls back to the collection base URI. The
+	/// decimals are declared when the token is created and can not be changed.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, Default)]
+	pub struct TokenMetadata<BoundedString> {
+		pub name: BoundedString,
+		pub symbol: BoundedString,
+		pub decimals: u8,
+		pub uri: BoundedString,
+	}
+
+	/// Royalty owed to `recipient` on every sale, as a `rate` of the sale price.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct RoyaltyInfo<AccountId> {
+		pub recipient: AccountId,
+		pub rate: Perbill,
+	}
+
+	pub(crate) type TokenMetadataOf<T> = TokenMetadata<BoundedVec<u8, <T as Config>::StringLimit>>;
+
+	/// Most decimals a token can have, so that one whole token fits into a `u128`.
+	pub const MAX_DECIMALS: u8 = 38;
+
+	/// Most entries returned by one page of `tokens_of` or `holders_of`.
+	pub const MAX_PAGE_SIZE: u32 = 100;
+
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
 
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
@@ -38,14 +90,78 @@ pub mod pallet {
 			+ Debug
 			+ Default
 			+ scale_info::TypeInfo;
+		/// Native currency which can be wrapped into the `WrappedTokenId` token.
+		type Currency: Currency<Self::AccountId, Balance = Self::Balance>;
+		/// Used to derive the account holding the wrapped native currency.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+		/// Token minted 1:1 for wrapped native currency, it can not be initialized.
+		#[pallet::constant]
+		type WrappedTokenId: Get<Self::TokenId>;
+		/// Token the native currency is exposed as next to the tokens of the pallet, e.g. by
+		/// `NativeAdapter`, it can not be initialized.
 		#[pallet::constant]
-		type Decimals: Get<u32>;
+		type NativeTokenId: Get<Self::TokenId>;
+		/// Maximum length of the metadata strings and of the base URI.
+		#[pallet::constant]
+		type StringLimit: Get<u32>;
+		/// Origin allowed to set the collection base URI.
+		type AdminOrigin: EnsureOrigin<Self::Origin>;
+		/// Maximum length of the data attached to a transfer.
+		#[pallet::constant]
+		type MaxDataLen: Get<u32>;
+		/// Called whenever tokens are transferred or minted to an account.
+		type TransferHook: TransferHook<Self::AccountId, Self::TokenId, Self::Balance>;
 	}
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
+	/// Initialized tokens, the `WrappedTokenId` token is always initialized and has no entry.
+	#[pallet::storage]
+	#[pallet::getter(fn get_token_info)]
+	pub(super) type TokenInfos<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::TokenId, TokenInfo<T::AccountId>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn is_minter)]
+	pub(super) type Minters<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::TokenId,
+		Blake2_128Concat,
+		T::AccountId,
+		bool,
+		ValueQuery,
+	>;
+
+	/// Accounts whose balance of a token is frozen by its admin, keyed by `(token_id, account)`.
+	#[pallet::storage]
+	#[pallet::getter(fn is_frozen)]
+	pub(super) type Frozen<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::TokenId,
+		Blake2_128Concat,
+		T::AccountId,
+		bool,
+		ValueQuery,
+	>;
+
+	/// Tokens created by `mint_nfts`, their supply is one and they can not be minted again.
+	#[pallet::storage]
+	#[pallet::getter(fn is_non_fungible)]
+	pub(super) type NonFungible<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::TokenId, bool, ValueQuery>;
+
+	/// Tokens frozen as a whole by their admin.
+	#[pallet::storage]
+	#[pallet::getter(fn is_token_frozen)]
+	pub(super) type FrozenTokens<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::TokenId, bool, ValueQuery>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn get_total_supply)]
 	pub(super) type TotalSupply<T: Config> =
@@ -63,6 +179,18 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
+	/// Index of `Balances` by token, it has an entry for every non-zero balance.
+	#[pallet::storage]
+	pub(super) type Holders<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::TokenId,
+		Blake2_128Concat,
+		T::AccountId,
+		(),
+		OptionQuery,
+	>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn get_approval)]
 	pub(super) type Approval<T: Config> = StorageDoubleMap<
@@ -75,13 +203,146 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
+	/// Amount of a token a spender may move from an owner, keyed by `(owner, spender, token_id)`.
+	#[pallet::storage]
+	#[pallet::getter(fn get_allowance)]
+	pub(super) type Allowances<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, T::AccountId>,
+			NMapKey<Blake2_128Concat, T::AccountId>,
+			NMapKey<Blake2_128Concat, T::TokenId>,
+		),
+		T::Balance,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_metadata)]
+	pub(super) type Metadata<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::TokenId, TokenMetadataOf<T>, ValueQuery>;
+
+	/// URI of the tokens without an own one, clients replace `{id}` by the token id as the
+	/// ERC1155 metadata URI extension specifies.
+	#[pallet::storage]
+	#[pallet::getter(fn get_base_uri)]
+	pub(super) type BaseUri<T: Config> =
+		StorageValue<_, BoundedVec<u8, T::StringLimit>, ValueQuery>;
+
+	/// Royalties set by the admins of the tokens.
+	#[pallet::storage]
+	#[pallet::getter(fn get_royalty)]
+	pub(super) type Royalties<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::TokenId, RoyaltyInfo<T::AccountId>>;
+
+	/// Royalty of the tokens without an own one.
+	#[pallet::storage]
+	#[pallet::getter(fn get_default_royalty)]
+	pub(super) type DefaultRoyalty<T: Config> = StorageValue<_, RoyaltyInfo<T::AccountId>>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// Tokens as `(token_id, admin, total_supply, decimals)`, the supply is given in base
+		/// units. The admin is the creator and the first minter of the token.
+		pub tokens: Vec<(T::TokenId, T::AccountId, T::Balance, u8)>,
+		/// Balances as `(account, token_id, balance)`, for every token they have to add up to its
+		/// total supply.
+		pub balances: Vec<(T::AccountId, T::TokenId, T::Balance)>,
+		/// Approvals as `(owner, operator)`.
+		pub approvals: Vec<(T::AccountId, T::AccountId)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { tokens: Vec::new(), balances: Vec::new(), approvals: Vec::new() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			for (token_id, admin, supply, decimals) in &self.tokens {
+				assert!(!supply.is_zero(), "token {:?} has zero total supply", token_id);
+				assert!(*decimals <= MAX_DECIMALS, "token {:?} has too many decimals", token_id);
+				assert!(
+					!Pallet::<T>::is_reserved(token_id),
+					"token {:?} is reserved for the native currency",
+					token_id
+				);
+				assert!(
+					!Pallet::<T>::is_initialized(token_id),
+					"token {:?} is defined more than once",
+					token_id
+				);
+				TotalSupply::<T>::insert(token_id, supply);
+				Pallet::<T>::create_token(admin, token_id, *decimals);
+			}
+			for (account, token_id, balance) in &self.balances {
+				assert!(
+					Pallet::<T>::is_initialized(token_id),
+					"balance of undefined token {:?}",
+					token_id
+				);
+				let total = Pallet::<T>::get_balance(account, token_id)
+					.checked_add(balance)
+					.expect("balance overflow");
+				Pallet::<T>::set_balance(account, token_id, total);
+			}
+			for (token_id, _, supply, _) in &self.tokens {
+				let distributed = self
+					.balances
+					.iter()
+					.filter(|(_, id, _)| id == token_id)
+					.fold(T::Balance::zero(), |sum, (_, _, balance)| sum.saturating_add(*balance));
+				assert!(
+					distributed == *supply,
+					"balances of token {:?} do not add up to its total supply",
+					token_id
+				);
+			}
+			for (owner, operator) in &self.approvals {
+				Approval::<T>::insert(owner, operator, true);
+			}
+		}
+	}
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		Initialized(T::AccountId, Vec<T::TokenId>, Vec<T::Balance>),
-		TransferSingle(T::AccountId, T::AccountId, T::TokenId, T::Balance),
-		TransferBatch(T::AccountId, T::AccountId, Vec<T::TokenId>, Vec<T::Balance>),
+		/// `(operator, from, to, token_id, amount, data)`
+		TransferSingle(T::AccountId, T::AccountId, T::AccountId, T::TokenId, T::Balance, Vec<u8>),
+		/// `(operator, from, to, token_ids, amounts, data)`
+		TransferBatch(
+			T::AccountId,
+			T::AccountId,
+			T::AccountId,
+			Vec<T::TokenId>,
+			Vec<T::Balance>,
+			Vec<u8>,
+		),
 		ApprovalForAll(T::AccountId, T::AccountId, bool),
+		/// `(owner, spender, token_id, allowance)`
+		Approval(T::AccountId, T::AccountId, T::TokenId, T::Balance),
+		Wrapped(T::AccountId, T::Balance),
+		Unwrapped(T::AccountId, T::Balance),
+		/// `(token_id, admin)`
+		AdminChanged(T::TokenId, T::AccountId),
+		/// `(token_id, minter)`
+		MinterAdded(T::TokenId, T::AccountId),
+		/// `(token_id, minter)`
+		MinterRemoved(T::TokenId, T::AccountId),
+		/// `(token_id, account)`
+		Frozen(T::TokenId, T::AccountId),
+		/// `(token_id, account)`
+		Thawed(T::TokenId, T::AccountId),
+		TokenFrozen(T::TokenId),
+		TokenThawed(T::TokenId),
+		/// `(royalty, token_id)`, `None` for the collection default royalty.
+		RoyaltyChanged(Option<RoyaltyInfo<T::AccountId>>, Option<T::TokenId>),
+		/// `(uri, token_id)`, `None` for the collection base URI.
+		URI(Vec<u8>, Option<T::TokenId>),
 	}
 
 	#[pallet::error]
@@ -96,23 +357,37 @@ pub mod pallet {
 		SelfTransfer,
 		ZeroAmountTransfer,
 		ZeroAdressTransfer,
+		ReservedTokenId,
+		InsufficientAllowance,
+		NotAdmin,
+		NotMinter,
+		BadMetadata,
+		TooManyDecimals,
+		AccountFrozen,
+		TokenFrozen,
+		NotFungible,
+		InvalidNftAmount,
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
+		/// Create `token_ids` with their `initial_supplies` in base units and their `decimals`.
 		#[pallet::weight(100)]
 		#[transactional]
 		pub fn init(
 			origin: OriginFor<T>,
 			token_ids: Vec<T::TokenId>,
 			initial_supplies: Vec<T::Balance>,
+			decimals: Vec<u8>,
 		) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
-			<Self as Erc1155<_>>::init(&sender, &token_ids, &initial_supplies)?;
+			<Self as Erc1155<_>>::init(&sender, &token_ids, &initial_supplies, &decimals)?;
 			Self::deposit_event(Event::Initialized(sender, token_ids, initial_supplies));
 			Ok(())
 		}
 
+		/// The signer has to be `from` or an operator approved by it, `data` is passed to the
+		/// `TransferHook` and included in the event.
 		#[pallet::weight(1000)]
 		#[transactional]
 		pub fn transfer_from_single(
@@ -121,13 +396,23 @@ pub mod pallet {
 			to: T::AccountId,
 			token_id: T::TokenId,
 			amount: T::Balance,
+			data: BoundedVec<u8, T::MaxDataLen>,
 		) -> DispatchResult {
-			let _sender = ensure_signed(origin)?;
-			<Self as Erc1155<_>>::transfer_from_single(&from, &to, &token_id, &amount)?;
-			Self::deposit_event(Event::TransferSingle(from, to, token_id, amount));
+			let sender = ensure_signed(origin)?;
+			Self::do_transfer_from_single(&sender, &from, &to, &token_id, &amount, &data)?;
+			Self::deposit_event(Event::TransferSingle(
+				sender,
+				from,
+				to,
+				token_id,
+				amount,
+				data.into_inner(),
+			));
 			Ok(())
 		}
 
+		/// The signer has to be `from` or an operator approved by it, `data` is passed to the
+		/// `TransferHook` and included in the event.
 		#[pallet::weight(1000)]
 		#[transactional]
 		pub fn transfer_from_batch(
@@ -136,10 +421,18 @@ pub mod pallet {
 			to: T::AccountId,
 			token_ids: Vec<T::TokenId>,
 			amounts: Vec<T::Balance>,
+			data: BoundedVec<u8, T::MaxDataLen>,
 		) -> DispatchResult {
-			let _sender = ensure_signed(origin)?;
-			<Self as Erc1155<_>>::transfer_from_batch(&from, &to, &token_ids, &amounts)?;
-			Self::deposit_event(Event::TransferBatch(from, to, token_ids, amounts));
+			let sender = ensure_signed(origin)?;
+			Self::do_transfer_from_batch(&sender, &from, &to, &token_ids, &amounts, &data)?;
+			Self::deposit_event(Event::TransferBatch(
+				sender,
+				from,
+				to,
+				token_ids,
+				amounts,
+				data.into_inner(),
+			));
 			Ok(())
 		}
 
@@ -155,6 +448,306 @@ pub mod pallet {
 			Self::deposit_event(Event::ApprovalForAll(sender, operator, approved));
 			Ok(())
 		}
+
+		/// Set the amount of `token_id` which `spender` may transfer from the signer.
+		#[pallet::weight(1000)]
+		pub fn approve(
+			origin: OriginFor<T>,
+			spender: T::AccountId,
+			token_id: T::TokenId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::token_initialized(&token_id)?;
+			Allowances::<T>::insert((&sender, &spender, token_id), amount);
+			Self::deposit_event(Event::Approval(sender, spender, token_id, amount));
+			Ok(())
+		}
+
+		#[pallet::weight(1000)]
+		pub fn increase_allowance(
+			origin: OriginFor<T>,
+			spender: T::AccountId,
+			token_id: T::TokenId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::token_initialized(&token_id)?;
+			let allowance = Self::get_allowance((&sender, &spender, token_id))
+				.checked_add(&amount)
+				.ok_or(Error::<T>::Overflow)?;
+			Allowances::<T>::insert((&sender, &spender, token_id), allowance);
+			Self::deposit_event(Event::Approval(sender, spender, token_id, allowance));
+			Ok(())
+		}
+
+		#[pallet::weight(1000)]
+		pub fn decrease_allowance(
+			origin: OriginFor<T>,
+			spender: T::AccountId,
+			token_id: T::TokenId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let allowance = Self::get_allowance((&sender, &spender, token_id))
+				.checked_sub(&amount)
+				.ok_or(Error::<T>::InsufficientAllowance)?;
+			Allowances::<T>::insert((&sender, &spender, token_id), allowance);
+			Self::deposit_event(Event::Approval(sender, spender, token_id, allowance));
+			Ok(())
+		}
+
+		/// Hand the admin role of `token_id` over to `admin`.
+		#[pallet::weight(1000)]
+		pub fn set_admin(
+			origin: OriginFor<T>,
+			token_id: T::TokenId,
+			admin: T::AccountId,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			TokenInfos::<T>::try_mutate(token_id, |info| -> DispatchResult {
+				let info = info.as_mut().ok_or(Error::<T>::Uninitilized)?;
+				ensure!(info.admin == sender, Error::<T>::NotAdmin);
+				info.admin = admin.clone();
+				Ok(())
+			})?;
+			Self::deposit_event(Event::AdminChanged(token_id, admin));
+			Ok(())
+		}
+
+		#[pallet::weight(1000)]
+		pub fn add_minter(
+			origin: OriginFor<T>,
+			token_id: T::TokenId,
+			minter: T::AccountId,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_admin(&sender, &token_id)?;
+			Minters::<T>::insert(token_id, &minter, true);
+			Self::deposit_event(Event::MinterAdded(token_id, minter));
+			Ok(())
+		}
+
+		#[pallet::weight(1000)]
+		pub fn remove_minter(
+			origin: OriginFor<T>,
+			token_id: T::TokenId,
+			minter: T::AccountId,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_admin(&sender, &token_id)?;
+			Minters::<T>::remove(token_id, &minter);
+			Self::deposit_event(Event::MinterRemoved(token_id, minter));
+			Ok(())
+		}
+
+		/// Stop `who` from transferring or burning `token_id`, it can still receive the token.
+		#[pallet::weight(1000)]
+		pub fn freeze(
+			origin: OriginFor<T>,
+			token_id: T::TokenId,
+			who: T::AccountId,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_admin(&sender, &token_id)?;
+			Frozen::<T>::insert(token_id, &who, true);
+			Self::deposit_event(Event::Frozen(token_id, who));
+			Ok(())
+		}
+
+		#[pallet::weight(1000)]
+		pub fn thaw(
+			origin: OriginFor<T>,
+			token_id: T::TokenId,
+			who: T::AccountId,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_admin(&sender, &token_id)?;
+			Frozen::<T>::remove(token_id, &who);
+			Self::deposit_event(Event::Thawed(token_id, who));
+			Ok(())
+		}
+
+		/// Stop every holder from transferring or burning `token_id`, minting stays possible.
+		#[pallet::weight(1000)]
+		pub fn freeze_token(origin: OriginFor<T>, token_id: T::TokenId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_admin(&sender, &token_id)?;
+			FrozenTokens::<T>::insert(token_id, true);
+			Self::deposit_event(Event::TokenFrozen(token_id));
+			Ok(())
+		}
+
+		#[pallet::weight(1000)]
+		pub fn thaw_token(origin: OriginFor<T>, token_id: T::TokenId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_admin(&sender, &token_id)?;
+			FrozenTokens::<T>::remove(token_id);
+			Self::deposit_event(Event::TokenThawed(token_id));
+			Ok(())
+		}
+
+		/// Create `amount` of `token_id` for `to`, the signer has to be a minter of the token.
+		#[pallet::weight(1000)]
+		#[transactional]
+		pub fn mint(
+			origin: OriginFor<T>,
+			to: T::AccountId,
+			token_id: T::TokenId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(Self::is_minter(token_id, &sender), Error::<T>::NotMinter);
+			Self::do_mint(&sender, &to, token_id, amount)
+		}
+
+		/// Create every one of `token_ids` as a non-fungible token owned by `to`, the signer
+		/// becomes their admin.
+		#[pallet::weight(1000)]
+		#[transactional]
+		pub fn mint_nfts(
+			origin: OriginFor<T>,
+			to: T::AccountId,
+			token_ids: Vec<T::TokenId>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(!token_ids.is_empty(), Error::<T>::InsufficientDataProvided);
+			ensure!(to != T::AccountId::default(), Error::<T>::ZeroAdressTransfer);
+			for token_id in &token_ids {
+				ensure!(!Self::is_reserved(token_id), Error::<T>::ReservedTokenId);
+				Self::token_uninitialized(token_id)?;
+				Self::create_token(&sender, token_id, 0);
+				NonFungible::<T>::insert(token_id, true);
+				TotalSupply::<T>::insert(token_id, T::Balance::one());
+				Self::set_balance(&to, token_id, One::one());
+			}
+			let amounts = sp_std::vec![T::Balance::one(); token_ids.len()];
+			let zero = T::AccountId::default();
+			T::TransferHook::on_batch_received(&sender, &zero, &to, &token_ids, &amounts, &[])?;
+			for token_id in token_ids {
+				Self::deposit_event(Event::TransferSingle(
+					sender.clone(),
+					zero.clone(),
+					to.clone(),
+					token_id,
+					One::one(),
+					Vec::new(),
+				));
+			}
+			Ok(())
+		}
+
+		/// Destroy `amount` of `token_id` owned by the signer.
+		#[pallet::weight(1000)]
+		#[transactional]
+		pub fn burn(
+			origin: OriginFor<T>,
+			token_id: T::TokenId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::do_burn(&sender, &sender, token_id, amount)
+		}
+
+		/// Set the metadata of `token_id`, the signer has to be the admin of the token.
+		#[pallet::weight(1000)]
+		pub fn set_metadata(
+			origin: OriginFor<T>,
+			token_id: T::TokenId,
+			name: Vec<u8>,
+			symbol: Vec<u8>,
+			uri: Vec<u8>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_admin(&sender, &token_id)?;
+			let bounded = |string: Vec<u8>| -> Result<BoundedVec<u8, T::StringLimit>, Error<T>> {
+				string.try_into().map_err(|_| Error::<T>::BadMetadata)
+			};
+			let (name, symbol, uri) = (bounded(name)?, bounded(symbol)?, bounded(uri)?);
+			Metadata::<T>::mutate(token_id, |metadata| {
+				metadata.name = name;
+				metadata.symbol = symbol;
+				metadata.uri = uri;
+			});
+			Self::deposit_event(Event::URI(Self::uri(token_id), Some(token_id)));
+			Ok(())
+		}
+
+		/// Set the URI of the tokens without an own one, it may contain `{id}`.
+		#[pallet::weight(1000)]
+		pub fn set_base_uri(origin: OriginFor<T>, uri: Vec<u8>) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let bounded: BoundedVec<u8, T::StringLimit> =
+				uri.clone().try_into().map_err(|_| Error::<T>::BadMetadata)?;
+			BaseUri::<T>::put(bounded);
+			Self::deposit_event(Event::URI(uri, None));
+			Ok(())
+		}
+
+		/// Set or clear the royalty of `token_id`, the signer has to be the admin of the token.
+		#[pallet::weight(1000)]
+		pub fn set_royalty(
+			origin: OriginFor<T>,
+			token_id: T::TokenId,
+			royalty: Option<RoyaltyInfo<T::AccountId>>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_admin(&sender, &token_id)?;
+			Royalties::<T>::set(token_id, royalty.clone());
+			Self::deposit_event(Event::RoyaltyChanged(royalty, Some(token_id)));
+			Ok(())
+		}
+
+		/// Set or clear the royalty of the tokens without an own one.
+		#[pallet::weight(1000)]
+		pub fn set_default_royalty(
+			origin: OriginFor<T>,
+			royalty: Option<RoyaltyInfo<T::AccountId>>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			DefaultRoyalty::<T>::set(royalty.clone());
+			Self::deposit_event(Event::RoyaltyChanged(royalty, None));
+			Ok(())
+		}
+
+		/// Lock `amount` of the native currency in the pallet account and mint the same amount
+		/// of the `WrappedTokenId` token.
+		#[pallet::weight(1000)]
+		#[transactional]
+		pub fn wrap(origin: OriginFor<T>, amount: T::Balance) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(!amount.is_zero(), Error::<T>::ZeroAmountTransfer);
+			T::Currency::transfer(
+				&sender,
+				&Self::account_id(),
+				amount,
+				ExistenceRequirement::KeepAlive,
+			)?;
+			Self::_mint(&sender, &sender, T::WrappedTokenId::get(), amount)?;
+			Self::deposit_event(Event::Wrapped(sender, amount));
+			Ok(())
+		}
+
+		/// Burn `amount` of the `WrappedTokenId` token and release the same amount of the native
+		/// currency. The locked native currency has to stay above the existential deposit
+		/// unless all of it is unwrapped.
+		#[pallet::weight(1000)]
+		#[transactional]
+		pub fn unwrap(origin: OriginFor<T>, amount: T::Balance) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(!amount.is_zero(), Error::<T>::ZeroAmountTransfer);
+			let token_id = T::WrappedTokenId::get();
+			let supply = Self::get_total_supply(token_id);
+			Self::_burn(&sender, &sender, token_id, amount)?;
+			let existence = if amount == supply {
+				ExistenceRequirement::AllowDeath
+			} else {
+				ExistenceRequirement::KeepAlive
+			};
+			T::Currency::transfer(&Self::account_id(), &sender, amount, existence)?;
+			Self::deposit_event(Event::Unwrapped(sender, amount));
+			Ok(())
+		}
 	}
 
 	impl<T: Config> Erc1155<T::AccountId> for Pallet<T> {
@@ -165,26 +758,22 @@ pub mod pallet {
 			who: &T::AccountId,
 			token_ids: &Vec<Self::TokenId>,
 			initial_supplies: &Vec<Self::Balance>,
+			decimals: &Vec<u8>,
 		) -> DispatchResult {
 			ensure!(
-				token_ids.len() == initial_supplies.len(),
+				token_ids.len() == initial_supplies.len() && token_ids.len() == decimals.len(),
 				Error::<T>::InsufficientDataProvided
 			);
-			for (token_id, supply) in token_ids.iter().zip(initial_supplies) {
-				let real_supply = supply
-					.checked_mul(
-						&(10 as u128)
-							.checked_pow(T::Decimals::get())
-							.unwrap()
-							.try_into()
-							.ok()
-							.unwrap(),
-					)
-					.ok_or(Error::<T>::Overflow)?;
+			for ((token_id, supply), decimals) in
+				token_ids.iter().zip(initial_supplies).zip(decimals)
+			{
+				ensure!(!Self::is_reserved(token_id), Error::<T>::ReservedTokenId);
 				Self::token_uninitialized(token_id)?;
-				ensure!(!real_supply.is_zero(), Error::<T>::ZeroSupplyProvided);
-				Balances::<T>::insert(who, token_id, real_supply);
-				TotalSupply::<T>::insert(token_id, real_supply);
+				ensure!(!supply.is_zero(), Error::<T>::ZeroSupplyProvided);
+				ensure!(*decimals <= MAX_DECIMALS, Error::<T>::TooManyDecimals);
+				Self::set_balance(who, token_id, *supply);
+				TotalSupply::<T>::insert(token_id, supply);
+				Self::create_token(who, token_id, *decimals);
 			}
 			Ok(())
 		}
@@ -216,79 +805,384 @@ pub mod pallet {
 			Ok(balance)
 		}
 
+		/// `to` pulls the tokens as an operator of `from`, like a DEX pool approved by `from`.
 		fn transfer_from_single(
 			from: &T::AccountId,
 			to: &T::AccountId,
 			token_id: &Self::TokenId,
 			amount: &Self::Balance,
+			data: &[u8],
 		) -> DispatchResult {
-			Self::token_initialized(&token_id)?;
-			ensure!(!amount.is_zero(), Error::<T>::ZeroAmountTransfer);
-			ensure!(to != &T::AccountId::default(), Error::<T>::ZeroAdressTransfer);
-			ensure!(from != to, Error::<T>::SelfTransfer);
-			ensure!(Self::get_approval(from, to), Error::<T>::TransferNotApproved);
-			Self::_transfer(from, to, token_id, amount)
+			Self::do_transfer_from_single(to, from, to, token_id, amount, data)
 		}
 
+		/// `to` pulls the tokens as an operator of `from`, like a DEX pool approved by `from`.
 		fn transfer_from_batch(
 			from: &T::AccountId,
 			to: &T::AccountId,
 			token_ids: &Vec<Self::TokenId>,
 			amounts: &Vec<Self::Balance>,
+			data: &[u8],
 		) -> DispatchResult {
-			ensure!(token_ids.len() == amounts.len(), Error::<T>::InsufficientDataProvided);
-			ensure!(to != &T::AccountId::default(), Error::<T>::ZeroAdressTransfer);
-			ensure!(from != to, <Error<T>>::SelfTransfer);
-			ensure!(Self::get_approval(from, to), Error::<T>::TransferNotApproved);
-			for (token_id, amount) in token_ids.iter().zip(amounts) {
-				Self::token_initialized(&token_id)?;
-				ensure!(!amount.is_zero(), Error::<T>::ZeroAmountTransfer);
-				Self::_transfer(from, to, token_id, amount)?;
-			}
-			Ok(())
+			Self::do_transfer_from_batch(to, from, to, token_ids, amounts, data)
 		}
 
+		#[transactional]
 		fn transfer(
 			from: &T::AccountId,
 			to: &T::AccountId,
 			token_id: Self::TokenId,
 			amount: Self::Balance,
+			data: &[u8],
 		) -> DispatchResult {
 			Self::token_initialized(&token_id)?;
 			ensure!(!amount.is_zero(), Error::<T>::ZeroAmountTransfer);
 			ensure!(to != &T::AccountId::default(), Error::<T>::ZeroAdressTransfer);
 			ensure!(from != to, Error::<T>::SelfTransfer);
-			Self::_transfer(from, to, &token_id, &amount)
+			Self::_transfer(from, to, &token_id, &amount)?;
+			T::TransferHook::on_received(from, from, to, token_id, amount, data)
 		}
 
 		fn set_approval_for_all(owner: &T::AccountId, operator: &T::AccountId, approved: bool) {
 			Approval::<T>::insert(owner, operator, approved);
 		}
+
+		/// Pallets mint as the zero account towards `TransferHook`.
+		fn mint(
+			to: &T::AccountId,
+			token_id: Self::TokenId,
+			amount: Self::Balance,
+		) -> DispatchResult {
+			Self::do_mint(&T::AccountId::default(), to, token_id, amount)
+		}
+
+		/// Pallets burn as the zero account towards `TransferHook`.
+		fn burn(
+			from: &T::AccountId,
+			token_id: Self::TokenId,
+			amount: Self::Balance,
+		) -> DispatchResult {
+			Self::do_burn(&T::AccountId::default(), from, token_id, amount)
+		}
+
+		fn is_approved_for_all(owner: &T::AccountId, operator: &T::AccountId) -> bool {
+			Self::get_approval(owner, operator)
+		}
+
+		fn allowance(
+			owner: &T::AccountId,
+			spender: &T::AccountId,
+			token_id: Self::TokenId,
+		) -> Self::Balance {
+			Self::get_allowance((owner, spender, token_id))
+		}
+
+		fn royalty_info(
+			token_id: Self::TokenId,
+			sale_price: Self::Balance,
+		) -> Option<(T::AccountId, Self::Balance)> {
+			if token_id == T::WrappedTokenId::get() || !Self::is_initialized(&token_id) {
+				return None
+			}
+			let royalty = Self::get_royalty(token_id).or_else(Self::get_default_royalty)?;
+			Some((royalty.recipient, royalty.rate.mul_floor(sale_price)))
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
+		/// Account holding the wrapped native currency, its balance backs the total supply of
+		/// the `WrappedTokenId` token.
+		pub fn account_id() -> T::AccountId {
+			T::PalletId::get().into_account()
+		}
+
+		/// Owner of the non-fungible `token_id`, `None` if it is not one or was burned.
+		pub fn owner_of(token_id: T::TokenId) -> Option<T::AccountId> {
+			if !Self::is_non_fungible(token_id) {
+				return None
+			}
+			Holders::<T>::iter_key_prefix(token_id).next()
+		}
+
+		/// URI of the metadata of `token_id`, with `{id}` left for clients to replace.
+		pub fn uri(token_id: T::TokenId) -> Vec<u8> {
+			let uri = Self::get_metadata(token_id).uri;
+			if uri.is_empty() {
+				Self::get_base_uri().into_inner()
+			} else {
+				uri.into_inner()
+			}
+		}
+
+		/// Metadata of `token_id` with the URI resolved by `uri`.
+		pub fn metadata(token_id: T::TokenId) -> Option<TokenMetadata<Vec<u8>>> {
+			if !Self::is_initialized(&token_id) {
+				return None
+			}
+			let metadata = Self::get_metadata(token_id);
+			Some(TokenMetadata {
+				name: metadata.name.into_inner(),
+				symbol: metadata.symbol.into_inner(),
+				decimals: metadata.decimals,
+				uri: Self::uri(token_id),
+			})
+		}
+
+		/// Page of the tokens `account` holds as `(token_id, balance)`, in storage order.
+		pub fn tokens_of(
+			account: &T::AccountId,
+			offset: u32,
+			limit: u32,
+		) -> Vec<(T::TokenId, T::Balance)> {
+			Balances::<T>::iter_prefix(account)
+				.skip(offset as usize)
+				.take(limit.min(MAX_PAGE_SIZE) as usize)
+				.collect()
+		}
+
+		/// Page of the holders of `token_id` as `(account, balance)`, in storage order.
+		pub fn holders_of(
+			token_id: T::TokenId,
+			offset: u32,
+			limit: u32,
+		) -> Vec<(T::AccountId, T::Balance)> {
+			Holders::<T>::iter_key_prefix(token_id)
+				.skip(offset as usize)
+				.take(limit.min(MAX_PAGE_SIZE) as usize)
+				.map(|account| {
+					let balance = Self::get_balance(&account, token_id);
+					(account, balance)
+				})
+				.collect()
+		}
+
+		/// `amount` base units of `token_id` in whole tokens as a decimal string without trailing
+		/// zeros, e.g. `1.5` for `1_500_000` base units of a token with 6 decimals.
+		pub fn display_amount(token_id: T::TokenId, amount: T::Balance) -> Vec<u8> {
+			let decimals = Self::get_metadata(token_id).decimals.min(MAX_DECIMALS);
+			let unit = 10u128.pow(decimals.into());
+			let amount = amount.saturated_into::<u128>();
+			let mut display = Self::digits(amount / unit, 1);
+			let mut fraction = Self::digits(amount % unit, decimals.into());
+			while fraction.last() == Some(&b'0') {
+				fraction.pop();
+			}
+			if !fraction.is_empty() {
+				display.push(b'.');
+				display.append(&mut fraction);
+			}
+			display
+		}
+
+		/// Decimal digits of `value`, padded with leading zeros to `min_len`.
+		fn digits(mut value: u128, min_len: usize) -> Vec<u8> {
+			let mut digits = Vec::new();
+			while value > 0 || digits.len() < min_len {
+				digits.push(b'0' + (value % 10) as u8);
+				value /= 10;
+			}
+			digits.reverse();
+			digits
+		}
+
+		/// Record `creator` as the creator, admin and first minter of `token_id`.
+		fn create_token(creator: &T::AccountId, token_id: &T::TokenId, decimals: u8) {
+			TokenInfos::<T>::insert(
+				token_id,
+				TokenInfo { creator: creator.clone(), admin: creator.clone() },
+			);
+			Minters::<T>::insert(token_id, creator, true);
+			Metadata::<T>::insert(token_id, TokenMetadata { decimals, ..Default::default() });
+		}
+
+		fn ensure_admin(who: &T::AccountId, token_id: &T::TokenId) -> DispatchResult {
+			let info = Self::get_token_info(token_id).ok_or(Error::<T>::Uninitilized)?;
+			ensure!(&info.admin == who, Error::<T>::NotAdmin);
+			Ok(())
+		}
+
+		pub(crate) fn ensure_not_frozen(
+			who: &T::AccountId,
+			token_id: &T::TokenId,
+		) -> DispatchResult {
+			ensure!(!Self::is_token_frozen(token_id), Error::<T>::TokenFrozen);
+			ensure!(!Self::is_frozen(token_id, who), Error::<T>::AccountFrozen);
+			Ok(())
+		}
+
+		fn do_mint(
+			operator: &T::AccountId,
+			to: &T::AccountId,
+			token_id: T::TokenId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			ensure!(token_id != T::WrappedTokenId::get(), Error::<T>::ReservedTokenId);
+			Self::_mint(operator, to, token_id, amount)
+		}
+
+		fn do_burn(
+			operator: &T::AccountId,
+			from: &T::AccountId,
+			token_id: T::TokenId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			ensure!(token_id != T::WrappedTokenId::get(), Error::<T>::ReservedTokenId);
+			Self::_burn(operator, from, token_id, amount)
+		}
+
+		/// Mint without rejecting the `WrappedTokenId` token, which only `wrap` may mint.
+		#[transactional]
+		fn _mint(
+			operator: &T::AccountId,
+			to: &T::AccountId,
+			token_id: T::TokenId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			Self::token_initialized(&token_id)?;
+			ensure!(!Self::is_non_fungible(token_id), Error::<T>::NotFungible);
+			ensure!(!amount.is_zero(), Error::<T>::ZeroAmountTransfer);
+			ensure!(to != &T::AccountId::default(), Error::<T>::ZeroAdressTransfer);
+			TotalSupply::<T>::try_mutate(token_id, |supply| -> DispatchResult {
+				*supply = supply.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+				Ok(())
+			})?;
+			// no balance exceeds the total supply
+			let balance = Self::get_balance(to, token_id).saturating_add(amount);
+			Self::set_balance(to, &token_id, balance);
+			let zero = T::AccountId::default();
+			T::TransferHook::on_received(operator, &zero, to, token_id, amount, &[])?;
+			Self::deposit_event(Event::TransferSingle(
+				operator.clone(),
+				zero,
+				to.clone(),
+				token_id,
+				amount,
+				Vec::new(),
+			));
+			Ok(())
+		}
+
+		/// Burn without rejecting the `WrappedTokenId` token, which only `unwrap` may burn.
+		#[transactional]
+		fn _burn(
+			operator: &T::AccountId,
+			from: &T::AccountId,
+			token_id: T::TokenId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			Self::token_initialized(&token_id)?;
+			ensure!(!amount.is_zero(), Error::<T>::ZeroAmountTransfer);
+			Self::ensure_not_frozen(from, &token_id)?;
+			let balance = Self::get_balance(from, token_id)
+				.checked_sub(&amount)
+				.ok_or(Error::<T>::InsufficientFunds)?;
+			Self::set_balance(from, &token_id, balance);
+			TotalSupply::<T>::mutate(token_id, |supply| *supply = supply.saturating_sub(amount));
+			let zero = T::AccountId::default();
+			T::TransferHook::on_received(operator, from, &zero, token_id, amount, &[])?;
+			Self::deposit_event(Event::TransferSingle(
+				operator.clone(),
+				from.clone(),
+				zero,
+				token_id,
+				amount,
+				Vec::new(),
+			));
+			Ok(())
+		}
+
+		#[transactional]
+		fn do_transfer_from_single(
+			operator: &T::AccountId,
+			from: &T::AccountId,
+			to: &T::AccountId,
+			token_id: &T::TokenId,
+			amount: &T::Balance,
+			data: &[u8],
+		) -> DispatchResult {
+			Self::token_initialized(&token_id)?;
+			ensure!(!amount.is_zero(), Error::<T>::ZeroAmountTransfer);
+			ensure!(to != &T::AccountId::default(), Error::<T>::ZeroAdressTransfer);
+			ensure!(from != to, Error::<T>::SelfTransfer);
+			Self::spend_allowance(operator, from, token_id, amount)?;
+			Self::_transfer(from, to, token_id, amount)?;
+			T::TransferHook::on_received(operator, from, to, *token_id, *amount, data)
+		}
+
+		#[transactional]
+		fn do_transfer_from_batch(
+			operator: &T::AccountId,
+			from: &T::AccountId,
+			to: &T::AccountId,
+			token_ids: &Vec<T::TokenId>,
+			amounts: &Vec<T::Balance>,
+			data: &[u8],
+		) -> DispatchResult {
+			ensure!(token_ids.len() == amounts.len(), Error::<T>::InsufficientDataProvided);
+			ensure!(to != &T::AccountId::default(), Error::<T>::ZeroAdressTransfer);
+			ensure!(from != to, <Error<T>>::SelfTransfer);
+			for (token_id, amount) in token_ids.iter().zip(amounts) {
+				Self::token_initialized(&token_id)?;
+				ensure!(!amount.is_zero(), Error::<T>::ZeroAmountTransfer);
+				Self::spend_allowance(operator, from, token_id, amount)?;
+				Self::_transfer(from, to, token_id, amount)?;
+			}
+			T::TransferHook::on_batch_received(operator, from, to, token_ids, amounts, data)
+		}
+
+		/// `operator` may move the tokens of `owner` if it is the owner or approved for all of
+		/// them, otherwise `amount` is taken from its allowance for `token_id`.
+		fn spend_allowance(
+			operator: &T::AccountId,
+			owner: &T::AccountId,
+			token_id: &T::TokenId,
+			amount: &T::Balance,
+		) -> DispatchResult {
+			if operator == owner || Self::get_approval(owner, operator) {
+				return Ok(())
+			}
+			let allowance = Self::get_allowance((owner, operator, token_id))
+				.checked_sub(amount)
+				.ok_or(Error::<T>::TransferNotApproved)?;
+			Allowances::<T>::insert((owner, operator, token_id), allowance);
+			Ok(())
+		}
+
 		fn _transfer(
 			from: &T::AccountId,
 			to: &T::AccountId,
 			token_id: &T::TokenId,
 			amount: &T::Balance,
 		) -> DispatchResult {
-			Balances::<T>::try_mutate(&from, &token_id, |balance| -> Result<(), Error<T>> {
-				let updated_sender_balance =
-					balance.checked_sub(&amount).ok_or(Error::<T>::InsufficientFunds)?;
-				*balance = updated_sender_balance;
-				Ok(())
-			})?;
-			Balances::<T>::try_mutate(&to, &token_id, |balance| -> Result<(), Error<T>> {
-				let updated_to_balance =
-					balance.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
-				*balance = updated_to_balance;
-				Ok(())
-			})?;
+			Self::ensure_not_frozen(from, token_id)?;
+			ensure!(
+				!Self::is_non_fungible(token_id) || amount.is_one(),
+				Error::<T>::InvalidNftAmount
+			);
+			let from_balance = Self::get_balance(from, token_id)
+				.checked_sub(amount)
+				.ok_or(Error::<T>::InsufficientFunds)?;
+			let to_balance = Self::get_balance(to, token_id)
+				.checked_add(amount)
+				.ok_or(Error::<T>::Overflow)?;
+			Self::set_balance(from, token_id, from_balance);
+			Self::set_balance(to, token_id, to_balance);
 			Ok(())
 		}
 
-		fn token_initialized(token_id: &T::TokenId) -> DispatchResult {
+		/// Balances are only written here, so that zero balances are removed and `Holders` stays
+		/// in sync with `Balances`.
+		pub(crate) fn set_balance(who: &T::AccountId, token_id: &T::TokenId, balance: T::Balance) {
+			if balance.is_zero() {
+				Balances::<T>::remove(who, token_id);
+				Holders::<T>::remove(token_id, who);
+			} else {
+				Balances::<T>::insert(who, token_id, balance);
+				Holders::<T>::insert(token_id, who, ());
+			}
+		}
+
+		pub(crate) fn token_initialized(token_id: &T::TokenId) -> DispatchResult {
 			ensure!(Self::is_initialized(token_id), Error::<T>::Uninitilized);
 			Ok(())
 		}
@@ -298,8 +1192,13 @@ pub mod pallet {
 			Ok(())
 		}
 
-		fn is_initialized(token_id: &T::TokenId) -> bool {
-			!Self::get_total_supply(&token_id).is_zero()
+		pub(crate) fn is_initialized(token_id: &T::TokenId) -> bool {
+			*token_id == T::WrappedTokenId::get() || TokenInfos::<T>::contains_key(token_id)
+		}
+
+		/// Tokens standing for the native currency, which can not be initialized.
+		fn is_reserved(token_id: &T::TokenId) -> bool {
+			*token_id == T::WrappedTokenId::get() || *token_id == T::NativeTokenId::get()
 		}
 	}
 }
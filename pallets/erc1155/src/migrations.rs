@@ -0,0 +1,136 @@
+//! Storage migrations of the ERC1155 pallet.
+
+use crate::pallet::{
+	Balances, Config, Holders, Metadata, Pallet, TokenInfo, TokenInfos, TotalSupply,
+};
+use frame_support::{
+	traits::{Get, GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+	weights::Weight,
+};
+use sp_runtime::traits::Zero;
+use sp_std::{marker::PhantomData, vec::Vec};
+
+pub mod v1 {
+	use super::*;
+
+	/// Records the tokens of the version 0 layout, which were identified by a non-zero total
+	/// supply, in `TokenInfos`.
+	///
+	/// Their creator is not known, so the zero account is recorded as the creator and the admin.
+	/// Without an admin nobody can be made a minter, so these tokens keep their fixed supply.
+	pub struct RecordTokenInfos<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for RecordTokenInfos<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 0 {
+				return T::DbWeight::get().reads(1)
+			}
+			let mut read = 0u64;
+			let mut migrated = 0u64;
+			for (token_id, supply) in TotalSupply::<T>::iter() {
+				read += 1;
+				if token_id == T::WrappedTokenId::get() || supply.is_zero() {
+					continue
+				}
+				let zero = T::AccountId::default();
+				TokenInfos::<T>::insert(token_id, TokenInfo { creator: zero.clone(), admin: zero });
+				migrated += 1;
+			}
+			StorageVersion::new(1).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(read + 1, migrated + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			use frame_support::ensure;
+
+			ensure!(Pallet::<T>::on_chain_storage_version() == 1, "storage version not updated");
+			for (token_id, _) in TotalSupply::<T>::iter() {
+				ensure!(
+					token_id == T::WrappedTokenId::get() || TokenInfos::<T>::contains_key(token_id),
+					"token info not recorded"
+				);
+			}
+			Ok(())
+		}
+	}
+}
+
+pub mod v2 {
+	use super::*;
+
+	/// Records `Decimals`, which was the global multiplier of the supplies given to `init`, as the
+	/// decimals of every token created before decimals were declared per token.
+	pub struct RecordDecimals<T, Decimals>(PhantomData<(T, Decimals)>);
+
+	impl<T: Config, Decimals: Get<u8>> OnRuntimeUpgrade for RecordDecimals<T, Decimals> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 1 {
+				return T::DbWeight::get().reads(1)
+			}
+			let mut migrated = 0u64;
+			for token_id in TokenInfos::<T>::iter_keys() {
+				Metadata::<T>::mutate(token_id, |metadata| metadata.decimals = Decimals::get());
+				migrated += 1;
+			}
+			StorageVersion::new(2).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(2 * migrated + 1, migrated + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			use frame_support::ensure;
+
+			ensure!(Pallet::<T>::on_chain_storage_version() == 2, "storage version not updated");
+			for token_id in TokenInfos::<T>::iter_keys() {
+				ensure!(
+					Metadata::<T>::get(token_id).decimals == Decimals::get(),
+					"decimals not recorded"
+				);
+			}
+			Ok(())
+		}
+	}
+}
+
+pub mod v3 {
+	use super::*;
+
+	/// Removes the zero balances and indexes the remaining ones by token in `Holders`.
+	pub struct IndexHolders<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for IndexHolders<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 2 {
+				return T::DbWeight::get().reads(1)
+			}
+			let mut read = 0u64;
+			let mut zero = Vec::new();
+			for (account, token_id, balance) in Balances::<T>::iter() {
+				read += 1;
+				if balance.is_zero() {
+					zero.push((account, token_id));
+				} else {
+					Holders::<T>::insert(token_id, account, ());
+				}
+			}
+			for (account, token_id) in &zero {
+				Balances::<T>::remove(account, token_id);
+			}
+			StorageVersion::new(3).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(read + 1, read + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			use frame_support::ensure;
+
+			ensure!(Pallet::<T>::on_chain_storage_version() == 3, "storage version not updated");
+			for (account, token_id, balance) in Balances::<T>::iter() {
+				ensure!(!balance.is_zero(), "zero balance not removed");
+				ensure!(Holders::<T>::contains_key(token_id, account), "holder not indexed");
+			}
+			Ok(())
+		}
+	}
+}
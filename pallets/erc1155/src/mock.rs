@@ -1,11 +1,18 @@
 use crate as pallet_erc1155;
-use frame_support::parameter_types;
+use frame_support::{
+	parameter_types, traits::tokens::BalanceConversion, weights::IdentityFee, PalletId,
+};
 use frame_system as system;
+use pallet_asset_tx_payment::FungiblesAdapter;
+use pallet_transaction_payment::CurrencyAdapter;
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
 	traits::{BlakeTwo256, IdentityLookup},
+	DispatchError, DispatchResult,
 };
+use std::cell::RefCell;
+use traits::TransferHook;
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -18,7 +25,10 @@ frame_support::construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-		Erc1155: pallet_erc1155::{Pallet, Call, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Erc1155: pallet_erc1155::{Pallet, Call, Storage, Event<T>, Config<T>},
+		TransactionPayment: pallet_transaction_payment::{Pallet, Storage},
+		AssetTxPayment: pallet_asset_tx_payment::{Pallet},
 	}
 );
 
@@ -45,7 +55,7 @@ impl system::Config for Test {
 	type BlockHashCount = BlockHashCount;
 	type Version = ();
 	type PalletInfo = PalletInfo;
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<u128>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 	type SystemWeightInfo = ();
@@ -54,16 +64,125 @@ impl system::Config for Test {
 }
 
 parameter_types! {
-	pub const Decimals: u32 = 6;
+	pub const ExistentialDeposit: u128 = 500;
+	pub const MaxLocks: u32 = 50;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = MaxLocks;
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u128;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = pallet_balances::weights::SubstrateWeight<Test>;
+}
+
+parameter_types! {
+	pub const NativeTokenId: u32 = 0;
+	pub const Erc1155PalletId: PalletId = PalletId(*b"erc/1155");
+	pub const WrappedTokenId: u32 = 100;
+	pub const StringLimit: u32 = 50;
+	pub const MaxDataLen: u32 = 32;
 }
 
 impl pallet_erc1155::Config for Test {
 	type Event = Event;
 	type TokenId = u32;
 	type Balance = u128;
-	type Decimals = Decimals;
+	type Currency = Balances;
+	type PalletId = Erc1155PalletId;
+	type WrappedTokenId = WrappedTokenId;
+	type NativeTokenId = NativeTokenId;
+	type StringLimit = StringLimit;
+	type AdminOrigin = system::EnsureRoot<u64>;
+	type MaxDataLen = MaxDataLen;
+	type TransferHook = RecordingHook;
+}
+
+parameter_types! {
+	pub const TransactionByteFee: u128 = 1;
+	pub const OperationalFeeMultiplier: u8 = 5;
+}
+
+impl pallet_transaction_payment::Config for Test {
+	type OnChargeTransaction = CurrencyAdapter<Balances, ()>;
+	type TransactionByteFee = TransactionByteFee;
+	type OperationalFeeMultiplier = OperationalFeeMultiplier;
+	type WeightToFee = IdentityFee<u128>;
+	type FeeMultiplierUpdate = ();
+}
+
+/// Prices fees paid in tokens 1:1 with the native currency.
+pub struct OneToOne;
+
+impl BalanceConversion<u128, u32, u128> for OneToOne {
+	type Error = DispatchError;
+
+	fn to_asset_balance(balance: u128, _token_id: u32) -> Result<u128, DispatchError> {
+		Ok(balance)
+	}
+}
+
+/// A stock FRAME pallet consuming the tokens of the pallet through `fungibles`.
+impl pallet_asset_tx_payment::Config for Test {
+	type Fungibles = Erc1155;
+	type OnChargeAssetTransaction = FungiblesAdapter<OneToOne, ()>;
 }
 
+/// Account whose `RecordingHook` rejects every token it receives.
+pub const REJECTING: u64 = 4;
+
+/// A transfer seen by `RecordingHook` as `(operator, from, to, token_ids, amounts, data)`.
+pub type Received = (u64, u64, u64, Vec<u32>, Vec<u128>, Vec<u8>);
+
+thread_local! {
+	static RECEIVED: RefCell<Vec<Received>> = RefCell::new(Vec::new());
+}
+
+/// Records the accepted transfers and rejects the ones to `REJECTING`.
+pub struct RecordingHook;
+
+impl RecordingHook {
+	pub fn received() -> Vec<Received> {
+		RECEIVED.with(|received| received.borrow().clone())
+	}
+}
+
+impl TransferHook<u64, u32, u128> for RecordingHook {
+	fn on_received(
+		operator: &u64,
+		from: &u64,
+		to: &u64,
+		token_id: u32,
+		amount: u128,
+		data: &[u8],
+	) -> DispatchResult {
+		Self::on_batch_received(operator, from, to, &[token_id], &[amount], data)
+	}
+
+	fn on_batch_received(
+		operator: &u64,
+		from: &u64,
+		to: &u64,
+		token_ids: &[u32],
+		amounts: &[u128],
+		data: &[u8],
+	) -> DispatchResult {
+		if *to == REJECTING {
+			return Err(DispatchError::Other("tokens rejected"))
+		}
+		let transfer = (*operator, *from, *to, token_ids.to_vec(), amounts.to_vec(), data.to_vec());
+		RECEIVED.with(|received| received.borrow_mut().push(transfer));
+		Ok(())
+	}
+}
+
+/// The native currency as the token `NativeTokenId` next to the tokens of the pallet.
+pub type NativeAndErc1155 = pallet_erc1155::NativeAdapter<Balances, Erc1155, NativeTokenId>;
+
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
 	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
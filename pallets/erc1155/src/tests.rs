@@ -1,11 +1,32 @@
-use crate::{mock::*, Error};
-use frame_support::{assert_noop, assert_ok};
+use crate::{mock::*, Error, RoyaltyInfo, TokenInfo, TokenMetadata};
+use frame_support::{
+	assert_noop, assert_ok, parameter_types,
+	traits::{
+		tokens::{
+			fungible::{self, ItemOf},
+			fungibles::{self, Inspect, Mutate},
+			DepositConsequence, WithdrawConsequence,
+		},
+		Currency, GenesisBuild,
+	},
+	weights::{DispatchInfo, Pays, PostDispatchInfo},
+};
+use pallet_asset_tx_payment::ChargeAssetTxPayment;
+use sp_runtime::{
+	traits::SignedExtension,
+	transaction_validity::{InvalidTransaction, TransactionValidityError},
+	DispatchError, Perbill,
+};
+use traits::Erc1155 as _;
 
 const TOKEN_0_ID: u32 = 1;
 const TOKEN_1_ID: u32 = 2;
 
+const NATIVE_TOKEN_ID: u32 = 0;
+
 const ALICE: u64 = 1;
 const BOB: u64 = 2;
+const CHARLIE: u64 = 3;
 
 const MIL: u128 = (10 as u128).pow(6);
 
@@ -15,7 +36,8 @@ fn init_should_work_1() {
 		assert_ok!(Erc1155::init(
 			Origin::signed(ALICE),
 			vec![TOKEN_0_ID, TOKEN_1_ID],
-			vec![1000, 1000]
+			vec![1000 * MIL, 1000 * MIL],
+			vec![6, 6]
 		));
 		assert_eq!(Erc1155::get_balance(ALICE, TOKEN_0_ID), 1000 * MIL);
 		assert_eq!(Erc1155::get_total_supply(TOKEN_0_ID), 1000 * MIL);
@@ -27,8 +49,13 @@ fn init_should_work_1() {
 #[test]
 fn init_should_work_2() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(Erc1155::init(Origin::signed(ALICE), vec![TOKEN_0_ID], vec![1000]));
-		assert_ok!(Erc1155::init(Origin::signed(BOB), vec![TOKEN_1_ID], vec![1000]));
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID],
+			vec![1000 * MIL],
+			vec![6]
+		));
+		assert_ok!(Erc1155::init(Origin::signed(BOB), vec![TOKEN_1_ID], vec![1000 * MIL], vec![6]));
 		assert_eq!(Erc1155::get_balance(ALICE, TOKEN_0_ID), 1000 * MIL);
 		assert_eq!(Erc1155::get_total_supply(TOKEN_0_ID), 1000 * MIL);
 		assert_eq!(Erc1155::get_balance(BOB, TOKEN_1_ID), 1000 * MIL);
@@ -36,16 +63,85 @@ fn init_should_work_2() {
 	});
 }
 
+#[test]
+fn genesis_should_work() {
+	new_test_ext().execute_with(|| {
+		crate::GenesisConfig::<Test> {
+			tokens: vec![(TOKEN_0_ID, ALICE, 1000 * MIL, 6), (TOKEN_1_ID, BOB, 500, 0)],
+			balances: vec![
+				(ALICE, TOKEN_0_ID, 600 * MIL),
+				(BOB, TOKEN_0_ID, 400 * MIL),
+				(BOB, TOKEN_1_ID, 500),
+			],
+			approvals: vec![(BOB, ALICE)],
+		}
+		.build();
+		assert_eq!(Erc1155::get_total_supply(TOKEN_0_ID), 1000 * MIL);
+		assert_eq!(Erc1155::get_total_supply(TOKEN_1_ID), 500);
+		assert_eq!(Erc1155::get_balance(ALICE, TOKEN_0_ID), 600 * MIL);
+		assert_eq!(Erc1155::get_balance(BOB, TOKEN_1_ID), 500);
+		assert!(Erc1155::get_approval(BOB, ALICE));
+		assert_eq!(
+			Erc1155::get_token_info(TOKEN_1_ID),
+			Some(TokenInfo { creator: BOB, admin: BOB })
+		);
+		assert!(Erc1155::is_minter(TOKEN_1_ID, BOB));
+		assert_ok!(Erc1155::transfer_from_single(
+			Origin::signed(ALICE),
+			BOB,
+			ALICE,
+			TOKEN_1_ID,
+			500,
+			Default::default()
+		));
+	});
+}
+
+#[test]
+#[should_panic(expected = "do not add up to its total supply")]
+fn genesis_should_fail() {
+	new_test_ext().execute_with(|| {
+		crate::GenesisConfig::<Test> {
+			tokens: vec![(TOKEN_0_ID, ALICE, 1000 * MIL, 6)],
+			balances: vec![(ALICE, TOKEN_0_ID, 600 * MIL), (BOB, TOKEN_0_ID, 300 * MIL)],
+			approvals: vec![],
+		}
+		.build();
+	});
+}
+
+#[test]
+fn init_should_take_base_units() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID, TOKEN_1_ID],
+			vec![1_500_000, 7],
+			vec![6, 0]
+		));
+		assert_eq!(Erc1155::get_balance(ALICE, TOKEN_0_ID), 1_500_000);
+		assert_eq!(Erc1155::get_metadata(TOKEN_0_ID).decimals, 6);
+		assert_eq!(Erc1155::display_amount(TOKEN_0_ID, 1_500_000), b"1.5".to_vec());
+		assert_eq!(Erc1155::display_amount(TOKEN_0_ID, 2_000_000), b"2".to_vec());
+		assert_eq!(Erc1155::display_amount(TOKEN_0_ID, 42), b"0.000042".to_vec());
+		assert_eq!(Erc1155::display_amount(TOKEN_0_ID, 0), b"0".to_vec());
+		// a token without decimals is counted in whole tokens
+		assert_eq!(Erc1155::get_balance(ALICE, TOKEN_1_ID), 7);
+		assert_eq!(Erc1155::display_amount(TOKEN_1_ID, 7), b"7".to_vec());
+	});
+}
+
 #[test]
 fn init_should_fail_1() {
 	new_test_ext().execute_with(|| {
 		assert_ok!(Erc1155::init(
 			Origin::signed(ALICE),
 			vec![TOKEN_0_ID, TOKEN_1_ID],
-			vec![1000, 1000]
+			vec![1000 * MIL, 1000 * MIL],
+			vec![6, 6]
 		));
 		assert_noop!(
-			Erc1155::init(Origin::signed(ALICE), vec![TOKEN_0_ID], vec![1000]),
+			Erc1155::init(Origin::signed(ALICE), vec![TOKEN_0_ID], vec![1000 * MIL], vec![6]),
 			Error::<Test>::AlreadyInitialized
 		);
 	});
@@ -55,7 +151,12 @@ fn init_should_fail_1() {
 fn init_should_fail_2() {
 	new_test_ext().execute_with(|| {
 		assert_noop!(
-			Erc1155::init(Origin::signed(ALICE), vec![TOKEN_0_ID, TOKEN_1_ID], vec![1000, 0]),
+			Erc1155::init(
+				Origin::signed(ALICE),
+				vec![TOKEN_0_ID, TOKEN_1_ID],
+				vec![1000 * MIL, 0],
+				vec![6, 6]
+			),
 			Error::<Test>::ZeroSupplyProvided
 		);
 	});
@@ -65,11 +166,21 @@ fn init_should_fail_2() {
 fn init_should_fail_3() {
 	new_test_ext().execute_with(|| {
 		assert_noop!(
-			Erc1155::init(Origin::signed(ALICE), vec![TOKEN_0_ID, TOKEN_1_ID], vec![1000]),
+			Erc1155::init(
+				Origin::signed(ALICE),
+				vec![TOKEN_0_ID, TOKEN_1_ID],
+				vec![1000 * MIL],
+				vec![6, 6]
+			),
 			Error::<Test>::InsufficientDataProvided
 		);
 		assert_noop!(
-			Erc1155::init(Origin::signed(ALICE), vec![TOKEN_1_ID], vec![1000, 100]),
+			Erc1155::init(
+				Origin::signed(ALICE),
+				vec![TOKEN_1_ID],
+				vec![1000 * MIL, 100 * MIL],
+				vec![6]
+			),
 			Error::<Test>::InsufficientDataProvided
 		);
 	});
@@ -79,8 +190,8 @@ fn init_should_fail_3() {
 fn init_should_fail_4() {
 	new_test_ext().execute_with(|| {
 		assert_noop!(
-			Erc1155::init(Origin::signed(ALICE), vec![TOKEN_0_ID], vec![u128::MAX]),
-			Error::<Test>::Overflow
+			Erc1155::init(Origin::signed(ALICE), vec![TOKEN_0_ID], vec![1000 * MIL], vec![39]),
+			Error::<Test>::TooManyDecimals
 		);
 	});
 }
@@ -88,7 +199,12 @@ fn init_should_fail_4() {
 #[test]
 fn set_approval_for_all_should_work() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(Erc1155::init(Origin::signed(ALICE), vec![TOKEN_0_ID], vec![1000]));
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID],
+			vec![1000 * MIL],
+			vec![6]
+		));
 		assert_ok!(Erc1155::set_approval_for_all(Origin::signed(ALICE), BOB, true));
 		assert_eq!(Erc1155::get_approval(ALICE, BOB), true);
 		assert_ok!(Erc1155::set_approval_for_all(Origin::signed(ALICE), BOB, false));
@@ -99,14 +215,20 @@ fn set_approval_for_all_should_work() {
 #[test]
 fn transfer_from_single_should_work() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(Erc1155::init(Origin::signed(ALICE), vec![TOKEN_0_ID], vec![1000]));
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID],
+			vec![1000 * MIL],
+			vec![6]
+		));
 		assert_ok!(Erc1155::set_approval_for_all(Origin::signed(ALICE), BOB, true));
 		assert_ok!(Erc1155::transfer_from_single(
 			Origin::signed(ALICE),
 			ALICE,
 			BOB,
 			TOKEN_0_ID,
-			100 * MIL
+			100 * MIL,
+			Default::default()
 		));
 		assert_eq!(Erc1155::get_balance(ALICE, TOKEN_0_ID), 900 * MIL);
 		assert_eq!(Erc1155::get_balance(BOB, TOKEN_0_ID), 100 * MIL);
@@ -116,9 +238,66 @@ fn transfer_from_single_should_work() {
 #[test]
 fn transfer_from_single_should_fail_1() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(Erc1155::init(Origin::signed(ALICE), vec![TOKEN_0_ID], vec![1000]));
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID],
+			vec![1000 * MIL],
+			vec![6]
+		));
+		assert_noop!(
+			Erc1155::transfer_from_single(
+				Origin::signed(BOB),
+				ALICE,
+				BOB,
+				TOKEN_0_ID,
+				100 * MIL,
+				Default::default()
+			),
+			Error::<Test>::TransferNotApproved
+		);
+	});
+}
+
+#[test]
+fn transfer_from_single_by_operator_should_work() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID],
+			vec![1000 * MIL],
+			vec![6]
+		));
+		assert_ok!(Erc1155::set_approval_for_all(Origin::signed(ALICE), BOB, true));
+		assert_ok!(Erc1155::transfer_from_single(
+			Origin::signed(BOB),
+			ALICE,
+			CHARLIE,
+			TOKEN_0_ID,
+			100 * MIL,
+			b"deposit 42".to_vec().try_into().unwrap()
+		));
+		assert_eq!(Erc1155::get_balance(ALICE, TOKEN_0_ID), 900 * MIL);
+		assert_eq!(Erc1155::get_balance(CHARLIE, TOKEN_0_ID), 100 * MIL);
+		System::assert_last_event(Event::Erc1155(crate::Event::TransferSingle(
+			BOB,
+			ALICE,
+			CHARLIE,
+			TOKEN_0_ID,
+			100 * MIL,
+			b"deposit 42".to_vec(),
+		)));
+
+		// an approval of the recipient does not let third parties move the tokens
 		assert_noop!(
-			Erc1155::transfer_from_single(Origin::signed(ALICE), ALICE, BOB, TOKEN_0_ID, 100 * MIL),
+			Erc1155::transfer_from_single(
+				Origin::signed(CHARLIE),
+				ALICE,
+				BOB,
+				TOKEN_0_ID,
+				MIL,
+				Default::default()
+			),
 			Error::<Test>::TransferNotApproved
 		);
 	});
@@ -127,9 +306,21 @@ fn transfer_from_single_should_fail_1() {
 #[test]
 fn transfer_from_single_should_fail_2() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(Erc1155::init(Origin::signed(ALICE), vec![TOKEN_0_ID], vec![1000]));
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID],
+			vec![1000 * MIL],
+			vec![6]
+		));
 		assert_noop!(
-			Erc1155::transfer_from_single(Origin::signed(ALICE), ALICE, BOB, TOKEN_0_ID, 0 * MIL),
+			Erc1155::transfer_from_single(
+				Origin::signed(ALICE),
+				ALICE,
+				BOB,
+				TOKEN_0_ID,
+				0 * MIL,
+				Default::default()
+			),
 			Error::<Test>::ZeroAmountTransfer
 		);
 	});
@@ -138,9 +329,21 @@ fn transfer_from_single_should_fail_2() {
 #[test]
 fn transfer_from_single_should_fail_3() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(Erc1155::init(Origin::signed(ALICE), vec![TOKEN_0_ID], vec![1000]));
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID],
+			vec![1000 * MIL],
+			vec![6]
+		));
 		assert_noop!(
-			Erc1155::transfer_from_single(Origin::signed(ALICE), ALICE, ALICE, TOKEN_0_ID, 1 * MIL),
+			Erc1155::transfer_from_single(
+				Origin::signed(ALICE),
+				ALICE,
+				ALICE,
+				TOKEN_0_ID,
+				1 * MIL,
+				Default::default()
+			),
 			Error::<Test>::SelfTransfer
 		);
 	});
@@ -149,10 +352,22 @@ fn transfer_from_single_should_fail_3() {
 #[test]
 fn transfer_from_single_should_fail_4() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(Erc1155::init(Origin::signed(ALICE), vec![TOKEN_0_ID], vec![1000]));
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID],
+			vec![1000 * MIL],
+			vec![6]
+		));
 		assert_ok!(Erc1155::set_approval_for_all(Origin::signed(ALICE), 0, true));
 		assert_noop!(
-			Erc1155::transfer_from_single(Origin::signed(ALICE), ALICE, 0, TOKEN_0_ID, 1 * MIL),
+			Erc1155::transfer_from_single(
+				Origin::signed(ALICE),
+				ALICE,
+				0,
+				TOKEN_0_ID,
+				1 * MIL,
+				Default::default()
+			),
 			Error::<Test>::ZeroAdressTransfer
 		);
 	});
@@ -161,7 +376,12 @@ fn transfer_from_single_should_fail_4() {
 #[test]
 fn transfer_from_single_should_fail_5() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(Erc1155::init(Origin::signed(ALICE), vec![TOKEN_0_ID], vec![1000]));
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID],
+			vec![1000 * MIL],
+			vec![6]
+		));
 		assert_ok!(Erc1155::set_approval_for_all(Origin::signed(ALICE), BOB, true));
 		assert_noop!(
 			Erc1155::transfer_from_single(
@@ -169,7 +389,8 @@ fn transfer_from_single_should_fail_5() {
 				ALICE,
 				BOB,
 				TOKEN_0_ID,
-				1001 * MIL
+				1001 * MIL,
+				Default::default()
 			),
 			Error::<Test>::InsufficientFunds
 		);
@@ -179,10 +400,22 @@ fn transfer_from_single_should_fail_5() {
 #[test]
 fn transfer_from_single_should_fail_6() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(Erc1155::init(Origin::signed(ALICE), vec![TOKEN_0_ID], vec![1000]));
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID],
+			vec![1000 * MIL],
+			vec![6]
+		));
 		assert_ok!(Erc1155::set_approval_for_all(Origin::signed(ALICE), BOB, true));
 		assert_noop!(
-			Erc1155::transfer_from_single(Origin::signed(ALICE), ALICE, BOB, TOKEN_1_ID, 50 * MIL),
+			Erc1155::transfer_from_single(
+				Origin::signed(ALICE),
+				ALICE,
+				BOB,
+				TOKEN_1_ID,
+				50 * MIL,
+				Default::default()
+			),
 			Error::<Test>::Uninitilized
 		);
 	});
@@ -194,7 +427,8 @@ fn transfer_from_batch_should_work() {
 		assert_ok!(Erc1155::init(
 			Origin::signed(ALICE),
 			vec![TOKEN_0_ID, TOKEN_1_ID],
-			vec![1000, 1000]
+			vec![1000 * MIL, 1000 * MIL],
+			vec![6, 6]
 		));
 		assert_ok!(Erc1155::set_approval_for_all(Origin::signed(ALICE), BOB, true));
 		assert_ok!(Erc1155::transfer_from_batch(
@@ -202,7 +436,8 @@ fn transfer_from_batch_should_work() {
 			ALICE,
 			BOB,
 			vec![TOKEN_0_ID, TOKEN_1_ID],
-			vec![900 * MIL, 50 * MIL]
+			vec![900 * MIL, 50 * MIL],
+			Default::default()
 		));
 		assert_eq!(Erc1155::get_balance(ALICE, TOKEN_0_ID), 100 * MIL);
 		assert_eq!(Erc1155::get_balance(ALICE, TOKEN_1_ID), 950 * MIL);
@@ -217,28 +452,63 @@ fn transfer_from_batch_should_fail_1() {
 		assert_ok!(Erc1155::init(
 			Origin::signed(ALICE),
 			vec![TOKEN_0_ID, TOKEN_1_ID],
-			vec![1000, 1000]
+			vec![1000 * MIL, 1000 * MIL],
+			vec![6, 6]
 		));
 		assert_noop!(
 			Erc1155::transfer_from_batch(
-				Origin::signed(ALICE),
+				Origin::signed(BOB),
 				ALICE,
 				BOB,
 				vec![TOKEN_0_ID, TOKEN_1_ID],
-				vec![900 * MIL, 50 * MIL]
+				vec![900 * MIL, 50 * MIL],
+				Default::default()
 			),
 			Error::<Test>::TransferNotApproved
 		);
 	});
 }
 
+#[test]
+fn transfer_from_batch_by_operator_should_work() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID, TOKEN_1_ID],
+			vec![1000 * MIL, 1000 * MIL],
+			vec![6, 6]
+		));
+		assert_ok!(Erc1155::set_approval_for_all(Origin::signed(ALICE), BOB, true));
+		assert_ok!(Erc1155::transfer_from_batch(
+			Origin::signed(BOB),
+			ALICE,
+			CHARLIE,
+			vec![TOKEN_0_ID, TOKEN_1_ID],
+			vec![900 * MIL, 50 * MIL],
+			Default::default()
+		));
+		assert_eq!(Erc1155::get_balance(CHARLIE, TOKEN_0_ID), 900 * MIL);
+		assert_eq!(Erc1155::get_balance(CHARLIE, TOKEN_1_ID), 50 * MIL);
+		System::assert_last_event(Event::Erc1155(crate::Event::TransferBatch(
+			BOB,
+			ALICE,
+			CHARLIE,
+			vec![TOKEN_0_ID, TOKEN_1_ID],
+			vec![900 * MIL, 50 * MIL],
+			vec![],
+		)));
+	});
+}
+
 #[test]
 fn transfer_from_batch_should_fail_2() {
 	new_test_ext().execute_with(|| {
 		assert_ok!(Erc1155::init(
 			Origin::signed(ALICE),
 			vec![TOKEN_0_ID, TOKEN_1_ID],
-			vec![1000, 1000]
+			vec![1000 * MIL, 1000 * MIL],
+			vec![6, 6]
 		));
 		assert_ok!(Erc1155::set_approval_for_all(Origin::signed(ALICE), BOB, true));
 		assert_noop!(
@@ -247,7 +517,8 @@ fn transfer_from_batch_should_fail_2() {
 				ALICE,
 				BOB,
 				vec![TOKEN_0_ID, TOKEN_1_ID],
-				vec![900 * MIL, 0]
+				vec![900 * MIL, 0],
+				Default::default()
 			),
 			Error::<Test>::ZeroAmountTransfer
 		);
@@ -260,7 +531,8 @@ fn transfer_from_batch_should_fail_3() {
 		assert_ok!(Erc1155::init(
 			Origin::signed(ALICE),
 			vec![TOKEN_0_ID, TOKEN_1_ID],
-			vec![1000, 1000]
+			vec![1000 * MIL, 1000 * MIL],
+			vec![6, 6]
 		));
 		assert_ok!(Erc1155::set_approval_for_all(Origin::signed(ALICE), BOB, true));
 		assert_noop!(
@@ -269,7 +541,8 @@ fn transfer_from_batch_should_fail_3() {
 				ALICE,
 				ALICE,
 				vec![TOKEN_0_ID, TOKEN_1_ID],
-				vec![900 * MIL, 50 * MIL]
+				vec![900 * MIL, 50 * MIL],
+				Default::default()
 			),
 			Error::<Test>::SelfTransfer
 		);
@@ -282,7 +555,8 @@ fn transfer_from_batch_should_fail_4() {
 		assert_ok!(Erc1155::init(
 			Origin::signed(ALICE),
 			vec![TOKEN_0_ID, TOKEN_1_ID],
-			vec![1000, 1000]
+			vec![1000 * MIL, 1000 * MIL],
+			vec![6, 6]
 		));
 		assert_ok!(Erc1155::set_approval_for_all(Origin::signed(ALICE), 0, true));
 		assert_noop!(
@@ -291,7 +565,8 @@ fn transfer_from_batch_should_fail_4() {
 				ALICE,
 				0,
 				vec![TOKEN_0_ID, TOKEN_1_ID],
-				vec![900 * MIL, 50 * MIL]
+				vec![900 * MIL, 50 * MIL],
+				Default::default()
 			),
 			Error::<Test>::ZeroAdressTransfer
 		);
@@ -304,7 +579,8 @@ fn transfer_from_batch_should_fail_5() {
 		assert_ok!(Erc1155::init(
 			Origin::signed(ALICE),
 			vec![TOKEN_0_ID, TOKEN_1_ID],
-			vec![1000, 1000]
+			vec![1000 * MIL, 1000 * MIL],
+			vec![6, 6]
 		));
 		assert_ok!(Erc1155::set_approval_for_all(Origin::signed(ALICE), BOB, true));
 		assert_noop!(
@@ -313,7 +589,8 @@ fn transfer_from_batch_should_fail_5() {
 				ALICE,
 				BOB,
 				vec![TOKEN_0_ID, TOKEN_1_ID],
-				vec![1001 * MIL, 50 * MIL]
+				vec![1001 * MIL, 50 * MIL],
+				Default::default()
 			),
 			Error::<Test>::InsufficientFunds
 		);
@@ -323,7 +600,12 @@ fn transfer_from_batch_should_fail_5() {
 #[test]
 fn transfer_from_batch_should_fail_6() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(Erc1155::init(Origin::signed(ALICE), vec![TOKEN_0_ID], vec![1000]));
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID],
+			vec![1000 * MIL],
+			vec![6]
+		));
 		assert_ok!(Erc1155::set_approval_for_all(Origin::signed(ALICE), BOB, true));
 		assert_noop!(
 			Erc1155::transfer_from_batch(
@@ -331,9 +613,1211 @@ fn transfer_from_batch_should_fail_6() {
 				ALICE,
 				BOB,
 				vec![TOKEN_0_ID, TOKEN_1_ID],
-				vec![100 * MIL, 50 * MIL]
+				vec![100 * MIL, 50 * MIL],
+				Default::default()
+			),
+			Error::<Test>::Uninitilized
+		);
+	});
+}
+
+#[test]
+fn allowance_should_work() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID, TOKEN_1_ID],
+			vec![1000 * MIL, 1000 * MIL],
+			vec![6, 6]
+		));
+		assert_ok!(Erc1155::approve(Origin::signed(ALICE), BOB, TOKEN_0_ID, 100 * MIL));
+		System::assert_last_event(Event::Erc1155(crate::Event::Approval(
+			ALICE,
+			BOB,
+			TOKEN_0_ID,
+			100 * MIL,
+		)));
+		assert_ok!(Erc1155::increase_allowance(Origin::signed(ALICE), BOB, TOKEN_0_ID, 50 * MIL));
+		assert_ok!(Erc1155::decrease_allowance(Origin::signed(ALICE), BOB, TOKEN_0_ID, 30 * MIL));
+		assert_eq!(Erc1155::get_allowance((ALICE, BOB, TOKEN_0_ID)), 120 * MIL);
+		assert_eq!(Erc1155::allowance(&ALICE, &BOB, TOKEN_0_ID), 120 * MIL);
+		assert_eq!(Erc1155::allowance(&ALICE, &BOB, TOKEN_1_ID), 0);
+
+		assert_ok!(Erc1155::transfer_from_single(
+			Origin::signed(BOB),
+			ALICE,
+			CHARLIE,
+			TOKEN_0_ID,
+			100 * MIL,
+			Default::default()
+		));
+		assert_eq!(Erc1155::get_allowance((ALICE, BOB, TOKEN_0_ID)), 20 * MIL);
+		assert_noop!(
+			Erc1155::transfer_from_single(
+				Origin::signed(BOB),
+				ALICE,
+				CHARLIE,
+				TOKEN_0_ID,
+				21 * MIL,
+				Default::default()
+			),
+			Error::<Test>::TransferNotApproved
+		);
+		// every token of a batch needs its own allowance
+		assert_noop!(
+			Erc1155::transfer_from_batch(
+				Origin::signed(BOB),
+				ALICE,
+				CHARLIE,
+				vec![TOKEN_0_ID, TOKEN_1_ID],
+				vec![10 * MIL, 10 * MIL],
+				Default::default()
 			),
+			Error::<Test>::TransferNotApproved
+		);
+		assert_ok!(Erc1155::transfer_from_batch(
+			Origin::signed(BOB),
+			ALICE,
+			CHARLIE,
+			vec![TOKEN_0_ID, TOKEN_0_ID],
+			vec![10 * MIL, 10 * MIL],
+			Default::default()
+		));
+		assert_eq!(Erc1155::get_allowance((ALICE, BOB, TOKEN_0_ID)), 0);
+		assert_eq!(Erc1155::get_balance(CHARLIE, TOKEN_0_ID), 120 * MIL);
+	});
+}
+
+#[test]
+fn allowance_should_fail() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID],
+			vec![1000 * MIL],
+			vec![6]
+		));
+		assert_noop!(
+			Erc1155::approve(Origin::signed(ALICE), BOB, TOKEN_1_ID, 100 * MIL),
 			Error::<Test>::Uninitilized
 		);
+		assert_ok!(Erc1155::approve(Origin::signed(ALICE), BOB, TOKEN_0_ID, u128::MAX));
+		assert_noop!(
+			Erc1155::increase_allowance(Origin::signed(ALICE), BOB, TOKEN_0_ID, 1),
+			Error::<Test>::Overflow
+		);
+		assert_ok!(Erc1155::approve(Origin::signed(ALICE), BOB, TOKEN_0_ID, 100 * MIL));
+		assert_noop!(
+			Erc1155::decrease_allowance(Origin::signed(ALICE), BOB, TOKEN_0_ID, 101 * MIL),
+			Error::<Test>::InsufficientAllowance
+		);
+	});
+}
+
+#[test]
+fn mint_and_burn_should_work() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID],
+			vec![1000 * MIL],
+			vec![6]
+		));
+		assert_eq!(
+			Erc1155::get_token_info(TOKEN_0_ID),
+			Some(TokenInfo { creator: ALICE, admin: ALICE })
+		);
+		assert!(Erc1155::is_minter(TOKEN_0_ID, ALICE));
+
+		assert_ok!(Erc1155::add_minter(Origin::signed(ALICE), TOKEN_0_ID, BOB));
+		assert_ok!(Erc1155::mint(Origin::signed(BOB), CHARLIE, TOKEN_0_ID, 100 * MIL));
+		System::assert_last_event(Event::Erc1155(crate::Event::TransferSingle(
+			BOB,
+			0,
+			CHARLIE,
+			TOKEN_0_ID,
+			100 * MIL,
+			vec![],
+		)));
+		assert_eq!(Erc1155::get_balance(CHARLIE, TOKEN_0_ID), 100 * MIL);
+		assert_eq!(Erc1155::get_total_supply(TOKEN_0_ID), 1100 * MIL);
+
+		assert_ok!(Erc1155::burn(Origin::signed(ALICE), TOKEN_0_ID, 1000 * MIL));
+		System::assert_last_event(Event::Erc1155(crate::Event::TransferSingle(
+			ALICE,
+			ALICE,
+			0,
+			TOKEN_0_ID,
+			1000 * MIL,
+			vec![],
+		)));
+		assert_eq!(Erc1155::get_balance(ALICE, TOKEN_0_ID), 0);
+		assert_eq!(Erc1155::get_total_supply(TOKEN_0_ID), 100 * MIL);
+
+		// a token stays initialized when all of it is burned
+		assert_ok!(Erc1155::burn(Origin::signed(CHARLIE), TOKEN_0_ID, 100 * MIL));
+		assert_eq!(Erc1155::total_supply(TOKEN_0_ID), Ok(0));
+		assert_ok!(Erc1155::mint(Origin::signed(ALICE), ALICE, TOKEN_0_ID, 1));
+		assert_eq!(Erc1155::get_total_supply(TOKEN_0_ID), 1);
+
+		// pallets mint and burn as the zero account
+		assert_ok!(<Erc1155 as traits::Erc1155<u64>>::mint(&BOB, TOKEN_0_ID, 5));
+		System::assert_last_event(Event::Erc1155(crate::Event::TransferSingle(
+			0,
+			0,
+			BOB,
+			TOKEN_0_ID,
+			5,
+			vec![],
+		)));
+		assert_ok!(<Erc1155 as traits::Erc1155<u64>>::burn(&BOB, TOKEN_0_ID, 2));
+		System::assert_last_event(Event::Erc1155(crate::Event::TransferSingle(
+			0,
+			BOB,
+			0,
+			TOKEN_0_ID,
+			2,
+			vec![],
+		)));
+		assert_eq!(Erc1155::get_balance(BOB, TOKEN_0_ID), 3);
+		assert_eq!(Erc1155::get_total_supply(TOKEN_0_ID), 4);
+	});
+}
+
+#[test]
+fn mint_and_burn_should_fail() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID],
+			vec![1000 * MIL],
+			vec![6]
+		));
+		assert_noop!(
+			Erc1155::mint(Origin::signed(BOB), BOB, TOKEN_0_ID, 1),
+			Error::<Test>::NotMinter
+		);
+		assert_noop!(
+			Erc1155::mint(Origin::signed(ALICE), BOB, TOKEN_1_ID, 1),
+			Error::<Test>::NotMinter
+		);
+		assert_noop!(
+			Erc1155::mint(Origin::signed(ALICE), 0, TOKEN_0_ID, 1),
+			Error::<Test>::ZeroAdressTransfer
+		);
+		assert_noop!(
+			Erc1155::mint(Origin::signed(ALICE), BOB, TOKEN_0_ID, u128::MAX),
+			Error::<Test>::Overflow
+		);
+		assert_noop!(
+			Erc1155::burn(Origin::signed(ALICE), TOKEN_0_ID, 1001 * MIL),
+			Error::<Test>::InsufficientFunds
+		);
+		assert_noop!(
+			Erc1155::burn(Origin::signed(ALICE), WrappedTokenId::get(), 1),
+			Error::<Test>::ReservedTokenId
+		);
+		assert_noop!(
+			NativeAndErc1155::mint(&ALICE, NATIVE_TOKEN_ID, 1),
+			DispatchError::Other("the native token can not be minted")
+		);
+	});
+}
+
+#[test]
+fn transfer_hook_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID, TOKEN_1_ID],
+			vec![1000 * MIL, 1000 * MIL],
+			vec![6, 6]
+		));
+		assert_ok!(Erc1155::set_approval_for_all(Origin::signed(ALICE), BOB, true));
+		assert_ok!(Erc1155::transfer_from_single(
+			Origin::signed(BOB),
+			ALICE,
+			CHARLIE,
+			TOKEN_0_ID,
+			MIL,
+			b"memo".to_vec().try_into().unwrap()
+		));
+		assert_ok!(Erc1155::transfer_from_batch(
+			Origin::signed(BOB),
+			ALICE,
+			CHARLIE,
+			vec![TOKEN_0_ID, TOKEN_1_ID],
+			vec![2 * MIL, 3 * MIL],
+			Default::default()
+		));
+		assert_ok!(Erc1155::mint(Origin::signed(ALICE), CHARLIE, TOKEN_1_ID, 4 * MIL));
+		assert_ok!(Erc1155::transfer(&CHARLIE, &BOB, TOKEN_1_ID, 5 * MIL, b"route"));
+		assert_ok!(Erc1155::burn(Origin::signed(BOB), TOKEN_1_ID, MIL));
+		assert_ok!(<Erc1155 as traits::Erc1155<u64>>::mint(&BOB, TOKEN_0_ID, MIL));
+		assert_ok!(<Erc1155 as traits::Erc1155<u64>>::burn(&BOB, TOKEN_0_ID, MIL));
+		assert_eq!(
+			RecordingHook::received(),
+			vec![
+				(BOB, ALICE, CHARLIE, vec![TOKEN_0_ID], vec![MIL], b"memo".to_vec()),
+				(BOB, ALICE, CHARLIE, vec![TOKEN_0_ID, TOKEN_1_ID], vec![2 * MIL, 3 * MIL], vec![]),
+				(ALICE, 0, CHARLIE, vec![TOKEN_1_ID], vec![4 * MIL], vec![]),
+				(CHARLIE, CHARLIE, BOB, vec![TOKEN_1_ID], vec![5 * MIL], b"route".to_vec()),
+				(BOB, BOB, 0, vec![TOKEN_1_ID], vec![MIL], vec![]),
+				(0, 0, BOB, vec![TOKEN_0_ID], vec![MIL], vec![]),
+				(0, BOB, 0, vec![TOKEN_0_ID], vec![MIL], vec![]),
+			]
+		);
+	});
+}
+
+#[test]
+fn transfer_hook_should_reject() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID, TOKEN_1_ID],
+			vec![1000 * MIL, 1000 * MIL],
+			vec![6, 6]
+		));
+		assert_ok!(Erc1155::approve(Origin::signed(ALICE), BOB, TOKEN_0_ID, MIL));
+		let rejected = DispatchError::Other("tokens rejected");
+		assert_noop!(
+			Erc1155::transfer_from_single(
+				Origin::signed(BOB),
+				ALICE,
+				REJECTING,
+				TOKEN_0_ID,
+				MIL,
+				Default::default()
+			),
+			rejected
+		);
+		assert_noop!(
+			Erc1155::transfer_from_batch(
+				Origin::signed(ALICE),
+				ALICE,
+				REJECTING,
+				vec![TOKEN_0_ID, TOKEN_1_ID],
+				vec![MIL, MIL],
+				Default::default()
+			),
+			rejected
+		);
+		assert_noop!(Erc1155::mint(Origin::signed(ALICE), REJECTING, TOKEN_0_ID, MIL), rejected);
+		assert_noop!(Erc1155::transfer(&ALICE, &REJECTING, TOKEN_0_ID, MIL, &[]), rejected);
+		assert_eq!(RecordingHook::received(), vec![]);
+	});
+}
+
+#[test]
+fn minter_roles_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID],
+			vec![1000 * MIL],
+			vec![6]
+		));
+		assert_noop!(
+			Erc1155::add_minter(Origin::signed(BOB), TOKEN_0_ID, BOB),
+			Error::<Test>::NotAdmin
+		);
+		assert_noop!(
+			Erc1155::add_minter(Origin::signed(ALICE), TOKEN_1_ID, BOB),
+			Error::<Test>::Uninitilized
+		);
+
+		assert_ok!(Erc1155::set_admin(Origin::signed(ALICE), TOKEN_0_ID, BOB));
+		assert_eq!(
+			Erc1155::get_token_info(TOKEN_0_ID),
+			Some(TokenInfo { creator: ALICE, admin: BOB })
+		);
+		assert_noop!(
+			Erc1155::set_admin(Origin::signed(ALICE), TOKEN_0_ID, ALICE),
+			Error::<Test>::NotAdmin
+		);
+		assert_ok!(Erc1155::remove_minter(Origin::signed(BOB), TOKEN_0_ID, ALICE));
+		assert_noop!(
+			Erc1155::mint(Origin::signed(ALICE), ALICE, TOKEN_0_ID, 1),
+			Error::<Test>::NotMinter
+		);
+	});
+}
+
+#[test]
+fn freeze_should_work() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID, TOKEN_1_ID],
+			vec![1000 * MIL, 1000 * MIL],
+			vec![6, 6]
+		));
+		assert_ok!(Erc1155::transfer(&ALICE, &BOB, TOKEN_0_ID, 100 * MIL, &[]));
+
+		assert_ok!(Erc1155::freeze(Origin::signed(ALICE), TOKEN_0_ID, BOB));
+		System::assert_last_event(Event::Erc1155(crate::Event::Frozen(TOKEN_0_ID, BOB)));
+		assert!(Erc1155::is_frozen(TOKEN_0_ID, BOB));
+		assert_noop!(
+			Erc1155::transfer_from_single(
+				Origin::signed(BOB),
+				BOB,
+				CHARLIE,
+				TOKEN_0_ID,
+				MIL,
+				Default::default()
+			),
+			Error::<Test>::AccountFrozen
+		);
+		assert_noop!(
+			Erc1155::transfer(&BOB, &CHARLIE, TOKEN_0_ID, MIL, &[]),
+			Error::<Test>::AccountFrozen
+		);
+		assert_noop!(
+			Erc1155::burn(Origin::signed(BOB), TOKEN_0_ID, MIL),
+			Error::<Test>::AccountFrozen
+		);
+		// a frozen account still receives the token and moves its other tokens
+		assert_ok!(Erc1155::transfer(&ALICE, &BOB, TOKEN_0_ID, MIL, &[]));
+		assert_ok!(Erc1155::transfer(&ALICE, &BOB, TOKEN_1_ID, MIL, &[]));
+		assert_ok!(Erc1155::transfer(&BOB, &CHARLIE, TOKEN_1_ID, MIL, &[]));
+
+		assert_ok!(Erc1155::thaw(Origin::signed(ALICE), TOKEN_0_ID, BOB));
+		System::assert_last_event(Event::Erc1155(crate::Event::Thawed(TOKEN_0_ID, BOB)));
+		assert_ok!(Erc1155::transfer(&BOB, &CHARLIE, TOKEN_0_ID, MIL, &[]));
+
+		assert_ok!(Erc1155::freeze_token(Origin::signed(ALICE), TOKEN_0_ID));
+		System::assert_last_event(Event::Erc1155(crate::Event::TokenFrozen(TOKEN_0_ID)));
+		assert_noop!(
+			Erc1155::transfer_from_batch(
+				Origin::signed(ALICE),
+				ALICE,
+				BOB,
+				vec![TOKEN_1_ID, TOKEN_0_ID],
+				vec![MIL, MIL],
+				Default::default()
+			),
+			Error::<Test>::TokenFrozen
+		);
+		assert_noop!(
+			Erc1155::burn(Origin::signed(ALICE), TOKEN_0_ID, MIL),
+			Error::<Test>::TokenFrozen
+		);
+		assert_ok!(Erc1155::mint(Origin::signed(ALICE), BOB, TOKEN_0_ID, MIL));
+
+		assert_ok!(Erc1155::thaw_token(Origin::signed(ALICE), TOKEN_0_ID));
+		System::assert_last_event(Event::Erc1155(crate::Event::TokenThawed(TOKEN_0_ID)));
+		assert_ok!(Erc1155::transfer(&ALICE, &BOB, TOKEN_0_ID, MIL, &[]));
+	});
+}
+
+#[test]
+fn freeze_should_fail() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID],
+			vec![1000 * MIL],
+			vec![6]
+		));
+		assert_noop!(
+			Erc1155::freeze(Origin::signed(BOB), TOKEN_0_ID, ALICE),
+			Error::<Test>::NotAdmin
+		);
+		assert_noop!(
+			Erc1155::thaw(Origin::signed(BOB), TOKEN_0_ID, ALICE),
+			Error::<Test>::NotAdmin
+		);
+		assert_noop!(
+			Erc1155::freeze_token(Origin::signed(BOB), TOKEN_0_ID),
+			Error::<Test>::NotAdmin
+		);
+		assert_noop!(Erc1155::thaw_token(Origin::signed(BOB), TOKEN_0_ID), Error::<Test>::NotAdmin);
+		assert_noop!(
+			Erc1155::freeze_token(Origin::signed(ALICE), TOKEN_1_ID),
+			Error::<Test>::Uninitilized
+		);
+		assert_noop!(
+			Erc1155::freeze_token(Origin::signed(ALICE), WrappedTokenId::get()),
+			Error::<Test>::Uninitilized
+		);
+	});
+}
+
+#[test]
+fn nft_should_work() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Erc1155::mint_nfts(Origin::signed(ALICE), BOB, vec![10, 11, 12]));
+		System::assert_last_event(Event::Erc1155(crate::Event::TransferSingle(
+			ALICE,
+			0,
+			BOB,
+			12,
+			1,
+			vec![],
+		)));
+		assert_eq!(
+			RecordingHook::received(),
+			vec![(ALICE, 0, BOB, vec![10, 11, 12], vec![1, 1, 1], vec![])]
+		);
+		for token_id in [10, 11, 12] {
+			assert!(Erc1155::is_non_fungible(token_id));
+			assert_eq!(Erc1155::get_total_supply(token_id), 1);
+			assert_eq!(Erc1155::get_balance(BOB, token_id), 1);
+			assert_eq!(Erc1155::owner_of(token_id), Some(BOB));
+			assert_eq!(
+				Erc1155::get_token_info(token_id),
+				Some(TokenInfo { creator: ALICE, admin: ALICE })
+			);
+		}
+
+		assert_ok!(Erc1155::transfer_from_single(
+			Origin::signed(BOB),
+			BOB,
+			CHARLIE,
+			11,
+			1,
+			Default::default()
+		));
+		assert_eq!(Erc1155::owner_of(11), Some(CHARLIE));
+		assert_ok!(Erc1155::burn(Origin::signed(CHARLIE), 11, 1));
+		assert_eq!(Erc1155::owner_of(11), None);
+		assert!(Erc1155::is_non_fungible(11));
+		assert_noop!(
+			Erc1155::mint(Origin::signed(ALICE), ALICE, 11, 1),
+			Error::<Test>::NotFungible
+		);
+		assert_noop!(
+			Erc1155::mint_nfts(Origin::signed(ALICE), ALICE, vec![11]),
+			Error::<Test>::AlreadyInitialized
+		);
+	});
+}
+
+#[test]
+fn nft_should_fail() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID],
+			vec![1000 * MIL],
+			vec![6]
+		));
+		assert_eq!(Erc1155::owner_of(TOKEN_0_ID), None);
+		assert_noop!(
+			Erc1155::mint_nfts(Origin::signed(ALICE), BOB, vec![10, TOKEN_0_ID]),
+			Error::<Test>::AlreadyInitialized
+		);
+		assert_noop!(
+			Erc1155::mint_nfts(Origin::signed(ALICE), BOB, vec![10, 10]),
+			Error::<Test>::AlreadyInitialized
+		);
+		assert_noop!(
+			Erc1155::mint_nfts(Origin::signed(ALICE), BOB, vec![WrappedTokenId::get()]),
+			Error::<Test>::ReservedTokenId
+		);
+		assert_noop!(
+			Erc1155::mint_nfts(Origin::signed(ALICE), 0, vec![10]),
+			Error::<Test>::ZeroAdressTransfer
+		);
+		assert_noop!(
+			Erc1155::mint_nfts(Origin::signed(ALICE), REJECTING, vec![10]),
+			DispatchError::Other("tokens rejected")
+		);
+
+		assert_ok!(Erc1155::mint_nfts(Origin::signed(ALICE), BOB, vec![10, 11]));
+		assert_noop!(
+			Erc1155::transfer_from_batch(
+				Origin::signed(BOB),
+				BOB,
+				CHARLIE,
+				vec![10, 11],
+				vec![1, 2],
+				Default::default()
+			),
+			Error::<Test>::InvalidNftAmount
+		);
+	});
+}
+
+#[test]
+fn royalty_should_work() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Erc1155::mint_nfts(Origin::signed(ALICE), BOB, vec![10, 11]));
+		assert_eq!(Erc1155::royalty_info(10, 1000), None);
+
+		let royalty = RoyaltyInfo { recipient: ALICE, rate: Perbill::from_percent(5) };
+		assert_ok!(Erc1155::set_royalty(Origin::signed(ALICE), 10, Some(royalty.clone())));
+		System::assert_last_event(Event::Erc1155(crate::Event::RoyaltyChanged(
+			Some(royalty),
+			Some(10),
+		)));
+		assert_eq!(Erc1155::royalty_info(10, 1000), Some((ALICE, 50)));
+		// the royalty is rounded down
+		assert_eq!(Erc1155::royalty_info(10, 39), Some((ALICE, 1)));
+		assert_eq!(Erc1155::royalty_info(11, 1000), None);
+
+		let default = RoyaltyInfo { recipient: CHARLIE, rate: Perbill::from_percent(1) };
+		assert_ok!(Erc1155::set_default_royalty(Origin::root(), Some(default.clone())));
+		System::assert_last_event(Event::Erc1155(crate::Event::RoyaltyChanged(
+			Some(default),
+			None,
+		)));
+		assert_eq!(Erc1155::royalty_info(10, 1000), Some((ALICE, 50)));
+		assert_eq!(Erc1155::royalty_info(11, 1000), Some((CHARLIE, 10)));
+		assert_eq!(Erc1155::royalty_info(12, 1000), None);
+		assert_eq!(NativeAndErc1155::royalty_info(11, 1000), Some((CHARLIE, 10)));
+		assert_eq!(NativeAndErc1155::royalty_info(NATIVE_TOKEN_ID, 1000), None);
+
+		assert_ok!(Erc1155::set_royalty(Origin::signed(ALICE), 10, None));
+		System::assert_last_event(Event::Erc1155(crate::Event::RoyaltyChanged(None, Some(10))));
+		assert_eq!(Erc1155::royalty_info(10, 1000), Some((CHARLIE, 10)));
+	});
+}
+
+#[test]
+fn royalty_should_fail() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Erc1155::mint_nfts(Origin::signed(ALICE), BOB, vec![10]));
+		let royalty = RoyaltyInfo { recipient: BOB, rate: Perbill::from_percent(5) };
+		assert_noop!(
+			Erc1155::set_royalty(Origin::signed(BOB), 10, Some(royalty.clone())),
+			Error::<Test>::NotAdmin
+		);
+		assert_noop!(
+			Erc1155::set_royalty(Origin::signed(ALICE), 11, Some(royalty.clone())),
+			Error::<Test>::Uninitilized
+		);
+		assert_noop!(
+			Erc1155::set_default_royalty(Origin::signed(ALICE), Some(royalty)),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn metadata_should_work() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID, TOKEN_1_ID],
+			vec![1000 * MIL, 1000 * MIL],
+			vec![6, 6]
+		));
+		assert_ok!(Erc1155::set_base_uri(Origin::root(), b"https://token-cdn/{id}.json".to_vec()));
+		System::assert_last_event(Event::Erc1155(crate::Event::URI(
+			b"https://token-cdn/{id}.json".to_vec(),
+			None,
+		)));
+		assert_ok!(Erc1155::set_metadata(
+			Origin::signed(ALICE),
+			TOKEN_0_ID,
+			b"Gold".to_vec(),
+			b"GLD".to_vec(),
+			b"ipfs://gold.json".to_vec()
+		));
+		System::assert_last_event(Event::Erc1155(crate::Event::URI(
+			b"ipfs://gold.json".to_vec(),
+			Some(TOKEN_0_ID),
+		)));
+		assert_eq!(
+			Erc1155::metadata(TOKEN_0_ID),
+			Some(TokenMetadata {
+				name: b"Gold".to_vec(),
+				symbol: b"GLD".to_vec(),
+				decimals: 6,
+				uri: b"ipfs://gold.json".to_vec(),
+			})
+		);
+		assert_eq!(Erc1155::get_metadata(TOKEN_0_ID).symbol.into_inner(), b"GLD".to_vec());
+
+		// tokens without an own URI use the base URI
+		assert_eq!(Erc1155::uri(TOKEN_1_ID), b"https://token-cdn/{id}.json".to_vec());
+		assert_eq!(
+			Erc1155::metadata(TOKEN_1_ID).map(|metadata| metadata.uri),
+			Some(b"https://token-cdn/{id}.json".to_vec())
+		);
+		assert_eq!(Erc1155::metadata(3), None);
+	});
+}
+
+#[test]
+fn metadata_should_fail() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID],
+			vec![1000 * MIL],
+			vec![6]
+		));
+		assert_noop!(
+			Erc1155::set_metadata(
+				Origin::signed(BOB),
+				TOKEN_0_ID,
+				b"Gold".to_vec(),
+				b"GLD".to_vec(),
+				vec![]
+			),
+			Error::<Test>::NotAdmin
+		);
+		assert_noop!(
+			Erc1155::set_metadata(
+				Origin::signed(ALICE),
+				TOKEN_0_ID,
+				vec![b'a'; 51],
+				b"GLD".to_vec(),
+				vec![]
+			),
+			Error::<Test>::BadMetadata
+		);
+		assert_noop!(
+			Erc1155::set_base_uri(Origin::signed(ALICE), b"https://token-cdn/{id}.json".to_vec()),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Erc1155::set_base_uri(Origin::root(), vec![b'a'; 51]),
+			Error::<Test>::BadMetadata
+		);
+	});
+}
+
+#[test]
+fn enumeration_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID, TOKEN_1_ID],
+			vec![1000 * MIL, 1000 * MIL],
+			vec![6, 6]
+		));
+		assert_ok!(Erc1155::transfer_from_single(
+			Origin::signed(ALICE),
+			ALICE,
+			BOB,
+			TOKEN_0_ID,
+			100 * MIL,
+			Default::default()
+		));
+		assert_ok!(Erc1155::transfer_from_single(
+			Origin::signed(ALICE),
+			ALICE,
+			CHARLIE,
+			TOKEN_0_ID,
+			200 * MIL,
+			Default::default()
+		));
+
+		let mut tokens = Erc1155::tokens_of(&ALICE, 0, 10);
+		tokens.sort();
+		assert_eq!(tokens, vec![(TOKEN_0_ID, 700 * MIL), (TOKEN_1_ID, 1000 * MIL)]);
+		let mut holders = Erc1155::holders_of(TOKEN_0_ID, 0, 10);
+		holders.sort();
+		assert_eq!(holders, vec![(ALICE, 700 * MIL), (BOB, 100 * MIL), (CHARLIE, 200 * MIL)]);
+
+		// pages do not overlap
+		let mut pages = Erc1155::holders_of(TOKEN_0_ID, 0, 2);
+		assert_eq!(pages.len(), 2);
+		pages.extend(Erc1155::holders_of(TOKEN_0_ID, 2, 2));
+		pages.sort();
+		assert_eq!(pages, holders);
+		assert_eq!(Erc1155::holders_of(TOKEN_0_ID, 3, 2), vec![]);
+
+		// zero balances are removed from storage and the index
+		assert_ok!(Erc1155::transfer_from_single(
+			Origin::signed(BOB),
+			BOB,
+			CHARLIE,
+			TOKEN_0_ID,
+			100 * MIL,
+			Default::default()
+		));
+		assert!(!crate::Balances::<Test>::contains_key(BOB, TOKEN_0_ID));
+		assert_eq!(Erc1155::tokens_of(&BOB, 0, 10), vec![]);
+		assert_ok!(Erc1155::burn(Origin::signed(ALICE), TOKEN_1_ID, 1000 * MIL));
+		assert_eq!(Erc1155::tokens_of(&ALICE, 0, 10), vec![(TOKEN_0_ID, 700 * MIL)]);
+		assert_eq!(Erc1155::holders_of(TOKEN_1_ID, 0, 10), vec![]);
+	});
+}
+
+#[test]
+fn migration_to_token_infos_should_work() {
+	use crate::migrations::v1::RecordTokenInfos;
+	use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+
+	new_test_ext().execute_with(|| {
+		// tokens of the version 0 layout only have a total supply
+		crate::TotalSupply::<Test>::insert(TOKEN_0_ID, 1000 * MIL);
+		crate::Balances::<Test>::insert(ALICE, TOKEN_0_ID, 1000 * MIL);
+		StorageVersion::new(0).put::<Erc1155>();
+
+		RecordTokenInfos::<Test>::on_runtime_upgrade();
+		assert_eq!(Erc1155::on_chain_storage_version(), 1);
+		assert_eq!(Erc1155::get_token_info(TOKEN_0_ID), Some(TokenInfo { creator: 0, admin: 0 }));
+		assert_eq!(Erc1155::get_token_info(TOKEN_1_ID), None);
+		assert_ok!(Erc1155::transfer_from_single(
+			Origin::signed(ALICE),
+			ALICE,
+			BOB,
+			TOKEN_0_ID,
+			100 * MIL,
+			Default::default()
+		));
+		assert_noop!(
+			Erc1155::mint(Origin::signed(ALICE), ALICE, TOKEN_0_ID, 1),
+			Error::<Test>::NotMinter
+		);
+	});
+}
+
+#[test]
+fn migration_to_decimals_should_work() {
+	use crate::migrations::v2::RecordDecimals;
+	use frame_support::{
+		parameter_types,
+		traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+	};
+
+	parameter_types! {
+		pub const LegacyDecimals: u8 = 6;
+	}
+
+	new_test_ext().execute_with(|| {
+		// tokens of the version 1 layout have no decimals
+		crate::TotalSupply::<Test>::insert(TOKEN_0_ID, 1000 * MIL);
+		crate::TokenInfos::<Test>::insert(TOKEN_0_ID, TokenInfo { creator: ALICE, admin: ALICE });
+		StorageVersion::new(1).put::<Erc1155>();
+
+		RecordDecimals::<Test, LegacyDecimals>::on_runtime_upgrade();
+		assert_eq!(Erc1155::on_chain_storage_version(), 2);
+		assert_eq!(Erc1155::get_metadata(TOKEN_0_ID).decimals, 6);
+		assert_eq!(Erc1155::display_amount(TOKEN_0_ID, 1000 * MIL), b"1000".to_vec());
+	});
+}
+
+#[test]
+fn migration_to_holders_index_should_work() {
+	use crate::migrations::v3::IndexHolders;
+	use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+
+	new_test_ext().execute_with(|| {
+		// balances of the version 2 layout are not indexed and may be zero
+		crate::TokenInfos::<Test>::insert(TOKEN_0_ID, TokenInfo { creator: ALICE, admin: ALICE });
+		crate::TotalSupply::<Test>::insert(TOKEN_0_ID, 1000 * MIL);
+		crate::Balances::<Test>::insert(ALICE, TOKEN_0_ID, 1000 * MIL);
+		crate::Balances::<Test>::insert(BOB, TOKEN_0_ID, 0);
+		StorageVersion::new(2).put::<Erc1155>();
+
+		IndexHolders::<Test>::on_runtime_upgrade();
+		assert_eq!(Erc1155::on_chain_storage_version(), 3);
+		assert!(!crate::Balances::<Test>::contains_key(BOB, TOKEN_0_ID));
+		assert_eq!(Erc1155::holders_of(TOKEN_0_ID, 0, 10), vec![(ALICE, 1000 * MIL)]);
+	});
+}
+
+#[test]
+fn native_adapter_should_work() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&ALICE, 10_000);
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID],
+			vec![1000 * MIL],
+			vec![6]
+		));
+		assert_eq!(NativeAndErc1155::balance_of(&ALICE, NATIVE_TOKEN_ID), Ok(10_000));
+		assert_eq!(NativeAndErc1155::total_supply(NATIVE_TOKEN_ID), Ok(10_000));
+		assert_eq!(
+			NativeAndErc1155::balance_of_batch(
+				vec![&ALICE, &ALICE],
+				vec![NATIVE_TOKEN_ID, TOKEN_0_ID]
+			),
+			Ok(vec![10_000, 1000 * MIL])
+		);
+
+		// native transfers are only approved for all tokens
+		assert_ok!(Erc1155::approve(Origin::signed(ALICE), BOB, TOKEN_0_ID, 10 * MIL));
+		assert_eq!(NativeAndErc1155::allowance(&ALICE, &BOB, NATIVE_TOKEN_ID), 0);
+		assert_noop!(
+			NativeAndErc1155::transfer_from_batch(
+				&ALICE,
+				&BOB,
+				&vec![NATIVE_TOKEN_ID, TOKEN_0_ID],
+				&vec![1000, 10 * MIL],
+				&[]
+			),
+			DispatchError::Other("the native token transfer is not approved")
+		);
+		assert_ok!(Erc1155::set_approval_for_all(Origin::signed(ALICE), BOB, true));
+		assert_eq!(NativeAndErc1155::allowance(&ALICE, &BOB, NATIVE_TOKEN_ID), u128::MAX);
+		// the native part of a batch is rolled back together with the rest of it
+		assert_noop!(
+			NativeAndErc1155::transfer_from_batch(
+				&ALICE,
+				&BOB,
+				&vec![NATIVE_TOKEN_ID, TOKEN_0_ID],
+				&vec![1000, 1001 * MIL],
+				&[]
+			),
+			Error::<Test>::InsufficientFunds
+		);
+		assert_ok!(NativeAndErc1155::transfer_from_batch(
+			&ALICE,
+			&BOB,
+			&vec![NATIVE_TOKEN_ID, TOKEN_0_ID],
+			&vec![1000, 10 * MIL],
+			&[]
+		));
+		assert_eq!(Balances::free_balance(&BOB), 1000);
+		assert_eq!(Erc1155::get_balance(BOB, TOKEN_0_ID), 10 * MIL);
+
+		assert_ok!(NativeAndErc1155::transfer(&BOB, &ALICE, NATIVE_TOKEN_ID, 500, &[]));
+		assert_eq!(Balances::free_balance(&ALICE), 9500);
+		assert_eq!(Balances::free_balance(&BOB), 500);
+	});
+}
+
+#[test]
+fn native_adapter_should_fail() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&ALICE, 10_000);
+		assert_noop!(
+			NativeAndErc1155::init(&ALICE, &vec![NATIVE_TOKEN_ID], &vec![1000 * MIL], &vec![6]),
+			DispatchError::Other("the native token can not be initialized")
+		);
+		assert_noop!(
+			Erc1155::init(Origin::signed(ALICE), vec![NATIVE_TOKEN_ID], vec![1000 * MIL], vec![6]),
+			Error::<Test>::ReservedTokenId
+		);
+		assert_noop!(
+			Erc1155::mint_nfts(Origin::signed(ALICE), ALICE, vec![NATIVE_TOKEN_ID]),
+			Error::<Test>::ReservedTokenId
+		);
+		assert_noop!(
+			NativeAndErc1155::transfer_from_single(&ALICE, &BOB, &NATIVE_TOKEN_ID, &100, &[]),
+			DispatchError::Other("the native token transfer is not approved")
+		);
+		// transfers from an account keep it alive
+		assert_ok!(Erc1155::set_approval_for_all(Origin::signed(ALICE), BOB, true));
+		assert_noop!(
+			NativeAndErc1155::transfer_from_single(&ALICE, &BOB, &NATIVE_TOKEN_ID, &10_000, &[]),
+			pallet_balances::Error::<Test>::KeepAlive
+		);
+		assert_noop!(
+			NativeAndErc1155::transfer(&ALICE, &BOB, NATIVE_TOKEN_ID, 10_000, &[]),
+			pallet_balances::Error::<Test>::KeepAlive
+		);
+		assert_noop!(
+			NativeAndErc1155::transfer(&ALICE, &BOB, NATIVE_TOKEN_ID, 100, &[]),
+			pallet_balances::Error::<Test>::ExistentialDeposit
+		);
+	});
+}
+
+#[test]
+fn wrap_should_work() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let wrapped = WrappedTokenId::get();
+		Balances::make_free_balance_be(&ALICE, 10_000);
+		assert_ok!(Erc1155::wrap(Origin::signed(ALICE), 2000));
+		System::assert_has_event(Event::Erc1155(crate::Event::TransferSingle(
+			ALICE,
+			0,
+			ALICE,
+			wrapped,
+			2000,
+			vec![],
+		)));
+		assert_eq!(Balances::free_balance(&ALICE), 8000);
+		assert_eq!(Balances::free_balance(&Erc1155::account_id()), 2000);
+		assert_eq!(Erc1155::get_balance(ALICE, wrapped), 2000);
+		assert_eq!(Erc1155::get_total_supply(wrapped), 2000);
+
+		assert_ok!(Erc1155::set_approval_for_all(Origin::signed(ALICE), BOB, true));
+		assert_ok!(Erc1155::transfer_from_single(
+			Origin::signed(ALICE),
+			ALICE,
+			BOB,
+			wrapped,
+			600,
+			Default::default()
+		));
+		assert_ok!(Erc1155::unwrap(Origin::signed(BOB), 600));
+		assert_eq!(Balances::free_balance(&BOB), 600);
+		assert_eq!(Erc1155::get_balance(BOB, wrapped), 0);
+		assert_eq!(Erc1155::get_total_supply(wrapped), 1400);
+		assert_eq!(Balances::free_balance(&Erc1155::account_id()), 1400);
+
+		// the last unwrap may empty the pallet account
+		assert_ok!(Erc1155::unwrap(Origin::signed(ALICE), 1400));
+		System::assert_has_event(Event::Erc1155(crate::Event::TransferSingle(
+			ALICE,
+			ALICE,
+			0,
+			wrapped,
+			1400,
+			vec![],
+		)));
+		assert_eq!(Balances::free_balance(&ALICE), 9400);
+		assert_eq!(Erc1155::get_total_supply(wrapped), 0);
+		assert_eq!(Balances::free_balance(&Erc1155::account_id()), 0);
+		assert_eq!(
+			RecordingHook::received(),
+			vec![
+				(ALICE, 0, ALICE, vec![wrapped], vec![2000], vec![]),
+				(ALICE, ALICE, BOB, vec![wrapped], vec![600], vec![]),
+				(BOB, BOB, 0, vec![wrapped], vec![600], vec![]),
+				(ALICE, ALICE, 0, vec![wrapped], vec![1400], vec![]),
+			]
+		);
+	});
+}
+
+#[test]
+fn wrap_should_fail() {
+	new_test_ext().execute_with(|| {
+		let wrapped = WrappedTokenId::get();
+		Balances::make_free_balance_be(&ALICE, 10_000);
+		assert_noop!(
+			Erc1155::init(Origin::signed(ALICE), vec![wrapped], vec![1000 * MIL], vec![6]),
+			Error::<Test>::ReservedTokenId
+		);
+		assert_noop!(Erc1155::wrap(Origin::signed(ALICE), 0), Error::<Test>::ZeroAmountTransfer);
+		assert_noop!(
+			Erc1155::wrap(Origin::signed(ALICE), 10_000),
+			pallet_balances::Error::<Test>::KeepAlive
+		);
+		assert_ok!(Erc1155::wrap(Origin::signed(ALICE), 2000));
+		assert_noop!(Erc1155::unwrap(Origin::signed(BOB), 1), Error::<Test>::InsufficientFunds);
+		// the locked balance would be dusted
+		assert_noop!(
+			Erc1155::unwrap(Origin::signed(ALICE), 1800),
+			pallet_balances::Error::<Test>::KeepAlive
+		);
+	});
+}
+
+#[test]
+fn fungibles_inspect_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID],
+			vec![1000 * MIL],
+			vec![6]
+		));
+		assert_ok!(Erc1155::transfer(&ALICE, &BOB, TOKEN_0_ID, 100 * MIL, &[]));
+		assert_eq!(Erc1155::total_issuance(TOKEN_0_ID), 1000 * MIL);
+		assert_eq!(Erc1155::minimum_balance(TOKEN_0_ID), 0);
+		assert_eq!(Erc1155::balance(TOKEN_0_ID, &BOB), 100 * MIL);
+		assert_eq!(Erc1155::reducible_balance(TOKEN_0_ID, &BOB, true), 100 * MIL);
+
+		assert_eq!(Erc1155::can_deposit(TOKEN_0_ID, &CHARLIE, 1), DepositConsequence::Success);
+		assert_eq!(
+			Erc1155::can_deposit(TOKEN_0_ID, &CHARLIE, u128::MAX),
+			DepositConsequence::Overflow
+		);
+		assert_eq!(Erc1155::can_deposit(TOKEN_1_ID, &CHARLIE, 1), DepositConsequence::UnknownAsset);
+		// nothing can be minted into the wrapped native currency and non-fungible tokens
+		assert_eq!(
+			Erc1155::can_deposit(WrappedTokenId::get(), &CHARLIE, 1),
+			DepositConsequence::UnknownAsset
+		);
+		assert_ok!(Erc1155::mint_nfts(Origin::signed(ALICE), BOB, vec![10]));
+		assert_eq!(Erc1155::can_deposit(10, &CHARLIE, 1), DepositConsequence::UnknownAsset);
+
+		assert_eq!(
+			Erc1155::can_withdraw(TOKEN_0_ID, &BOB, 100 * MIL),
+			WithdrawConsequence::Success
+		);
+		assert_eq!(
+			Erc1155::can_withdraw(TOKEN_0_ID, &BOB, 100 * MIL + 1),
+			WithdrawConsequence::NoFunds
+		);
+		assert_eq!(Erc1155::can_withdraw(TOKEN_1_ID, &BOB, 1), WithdrawConsequence::UnknownAsset);
+		assert_eq!(
+			Erc1155::can_withdraw(WrappedTokenId::get(), &BOB, 1),
+			WithdrawConsequence::UnknownAsset
+		);
+		assert_eq!(Erc1155::can_withdraw(10, &BOB, 1), WithdrawConsequence::Success);
+		assert_ok!(Erc1155::freeze(Origin::signed(ALICE), TOKEN_0_ID, BOB));
+		assert_eq!(Erc1155::reducible_balance(TOKEN_0_ID, &BOB, false), 0);
+		assert_eq!(Erc1155::can_withdraw(TOKEN_0_ID, &BOB, 1), WithdrawConsequence::Frozen);
+		assert_eq!(Erc1155::can_withdraw(TOKEN_0_ID, &ALICE, 1), WithdrawConsequence::Success);
+	});
+}
+
+#[test]
+fn fungibles_mutate_and_transfer_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID],
+			vec![1000 * MIL],
+			vec![6]
+		));
+		assert_ok!(Erc1155::mint_into(TOKEN_0_ID, &BOB, 100 * MIL));
+		assert_ok!(Erc1155::mint_into(TOKEN_0_ID, &BOB, 0));
+		assert_eq!(Erc1155::balance(TOKEN_0_ID, &BOB), 100 * MIL);
+		assert_eq!(Erc1155::total_issuance(TOKEN_0_ID), 1100 * MIL);
+		assert_eq!(Erc1155::burn_from(TOKEN_0_ID, &BOB, 40 * MIL), Ok(40 * MIL));
+		assert_eq!(Erc1155::total_issuance(TOKEN_0_ID), 1060 * MIL);
+
+		assert_eq!(
+			<Erc1155 as fungibles::Transfer<u64>>::transfer(
+				TOKEN_0_ID,
+				&BOB,
+				&CHARLIE,
+				60 * MIL,
+				true
+			),
+			Ok(60 * MIL)
+		);
+		assert_eq!(Erc1155::balance(TOKEN_0_ID, &BOB), 0);
+		assert_eq!(Erc1155::balance(TOKEN_0_ID, &CHARLIE), 60 * MIL);
+		assert_noop!(
+			<Erc1155 as fungibles::Transfer<u64>>::transfer(TOKEN_0_ID, &BOB, &CHARLIE, 1, false),
+			Error::<Test>::InsufficientFunds
+		);
+		assert_noop!(Erc1155::burn_from(TOKEN_0_ID, &BOB, 1), Error::<Test>::InsufficientFunds);
+		assert_noop!(
+			Erc1155::mint_into(WrappedTokenId::get(), &BOB, 1),
+			Error::<Test>::ReservedTokenId
+		);
+		assert_ok!(Erc1155::mint_nfts(Origin::signed(ALICE), BOB, vec![10]));
+		assert_noop!(Erc1155::mint_into(10, &BOB, 1), Error::<Test>::NotFungible);
+	});
+}
+
+#[test]
+fn fungibles_unbalanced_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID],
+			vec![1000 * MIL],
+			vec![6]
+		));
+		assert_ok!(<Erc1155 as fungibles::Unbalanced<u64>>::set_balance(
+			TOKEN_0_ID,
+			&BOB,
+			10 * MIL
+		));
+		assert_eq!(Erc1155::balance(TOKEN_0_ID, &BOB), 10 * MIL);
+		<Erc1155 as fungibles::Unbalanced<u64>>::set_total_issuance(TOKEN_0_ID, 1010 * MIL);
+		assert_eq!(Erc1155::total_issuance(TOKEN_0_ID), 1010 * MIL);
+
+		// frozen balances can still be raised, but not lowered
+		assert_ok!(Erc1155::freeze(Origin::signed(ALICE), TOKEN_0_ID, BOB));
+		assert_noop!(
+			<Erc1155 as fungibles::Unbalanced<u64>>::set_balance(TOKEN_0_ID, &BOB, 5 * MIL),
+			Error::<Test>::AccountFrozen
+		);
+		assert_ok!(<Erc1155 as fungibles::Unbalanced<u64>>::set_balance(
+			TOKEN_0_ID,
+			&BOB,
+			20 * MIL
+		));
+		assert_ok!(Erc1155::thaw(Origin::signed(ALICE), TOKEN_0_ID, BOB));
+		assert_ok!(Erc1155::freeze_token(Origin::signed(ALICE), TOKEN_0_ID));
+		assert_noop!(
+			<Erc1155 as fungibles::Unbalanced<u64>>::set_balance(TOKEN_0_ID, &BOB, 5 * MIL),
+			Error::<Test>::TokenFrozen
+		);
+		assert_ok!(Erc1155::thaw_token(Origin::signed(ALICE), TOKEN_0_ID));
+		assert_ok!(<Erc1155 as fungibles::Unbalanced<u64>>::set_balance(TOKEN_0_ID, &BOB, 5 * MIL));
+		assert_eq!(Erc1155::balance(TOKEN_0_ID, &BOB), 5 * MIL);
+
+		// the wrapped native currency stays backed by the locked native currency
+		Balances::make_free_balance_be(&ALICE, 10_000);
+		assert_ok!(Erc1155::wrap(Origin::signed(ALICE), 2000));
+		let wrapped = WrappedTokenId::get();
+		assert_noop!(
+			<Erc1155 as fungibles::Unbalanced<u64>>::set_balance(wrapped, &ALICE, 0),
+			Error::<Test>::ReservedTokenId
+		);
+		<Erc1155 as fungibles::Unbalanced<u64>>::set_total_issuance(wrapped, 0);
+		assert_eq!(Erc1155::total_issuance(wrapped), 2000);
+		// and can not be withdrawn through `fungibles`
+		assert_eq!(Erc1155::balance(wrapped, &ALICE), 2000);
+		assert_eq!(Erc1155::reducible_balance(wrapped, &ALICE, false), 0);
+		assert_eq!(Erc1155::can_withdraw(wrapped, &ALICE, 1), WithdrawConsequence::UnknownAsset);
+	});
+}
+
+parameter_types! {
+	pub const Token0: u32 = TOKEN_0_ID;
+}
+
+/// `ItemOf` is how FRAME code written for one `fungible` currency uses a `fungibles` asset.
+type Token0Currency = ItemOf<Erc1155, Token0, u64>;
+
+#[test]
+fn fungibles_should_work_through_item_of() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID],
+			vec![1000 * MIL],
+			vec![6]
+		));
+		assert_eq!(<Token0Currency as fungible::Inspect<u64>>::total_issuance(), 1000 * MIL);
+		assert_eq!(
+			<Token0Currency as fungible::Transfer<u64>>::transfer(&ALICE, &BOB, 10 * MIL, true),
+			Ok(10 * MIL)
+		);
+		assert_ok!(<Token0Currency as fungible::Mutate<u64>>::mint_into(&BOB, 5 * MIL));
+		assert_eq!(<Token0Currency as fungible::Inspect<u64>>::balance(&BOB), 15 * MIL);
+		assert_eq!(Erc1155::get_balance(BOB, TOKEN_0_ID), 15 * MIL);
+		assert_eq!(Erc1155::get_total_supply(TOKEN_0_ID), 1005 * MIL);
+	});
+}
+
+#[test]
+fn fees_should_be_paid_in_tokens_through_asset_tx_payment() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Erc1155::init(
+			Origin::signed(ALICE),
+			vec![TOKEN_0_ID],
+			vec![1000 * MIL],
+			vec![6]
+		));
+		let call = Call::System(frame_system::Call::remark { remark: vec![] });
+		let info = DispatchInfo { weight: 1000, ..Default::default() };
+		let post_info = PostDispatchInfo { actual_weight: None, pays_fee: Pays::Yes };
+		let len = 10;
+		let fee = TransactionPayment::compute_fee(len as u32, &info, 0);
+		assert!(fee > 0);
+
+		let pre = ChargeAssetTxPayment::<Test>::from(0, Some(TOKEN_0_ID))
+			.pre_dispatch(&ALICE, &call, &info, len)
+			.unwrap();
+		assert_eq!(Erc1155::get_balance(ALICE, TOKEN_0_ID), 1000 * MIL - fee);
+		assert_ok!(ChargeAssetTxPayment::<Test>::post_dispatch(
+			pre,
+			&info,
+			&post_info,
+			len,
+			&Ok(())
+		));
+		// the fee is burned
+		assert_eq!(Erc1155::get_balance(ALICE, TOKEN_0_ID), 1000 * MIL - fee);
+		assert_eq!(Erc1155::get_total_supply(TOKEN_0_ID), 1000 * MIL - fee);
+
+		// the wrapped native currency can not be taken through `fungibles`
+		Balances::make_free_balance_be(&ALICE, 10 * fee);
+		assert_ok!(Erc1155::wrap(Origin::signed(ALICE), 5 * fee));
+		assert!(matches!(
+			ChargeAssetTxPayment::<Test>::from(0, Some(WrappedTokenId::get()))
+				.pre_dispatch(&ALICE, &call, &info, len),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::Payment))
+		));
+		assert_eq!(Erc1155::get_balance(ALICE, WrappedTokenId::get()), 5 * fee);
 	});
 }
@@ -0,0 +1,182 @@
+//! Transaction fees payable in ERC1155 tokens through the DEX.
+
+use crate::{
+	AccountId, Balance, Balances, BlockLength, BlockWeights, Call, Dex, ExistentialDeposit,
+	NativeTokenId, OperationalFeeMultiplier, Runtime,
+};
+use codec::{Decode, Encode};
+use frame_support::weights::{DispatchClass, DispatchInfo};
+use pallet_dex::PoolId;
+use pallet_transaction_payment::ChargeTransactionPayment;
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{
+		DispatchInfoOf, One, PostDispatchInfoOf, SaturatedConversion, Saturating, SignedExtension,
+		Zero,
+	},
+	transaction_validity::{
+		InvalidTransaction, TransactionPriority, TransactionValidity, TransactionValidityError,
+		ValidTransaction,
+	},
+	DispatchResult,
+};
+use traits::Erc1155;
+
+type TokenId = u32;
+
+/// Replacement of `ChargeTransactionPayment` which lets the signer pay the fee in `fee_token`.
+///
+/// The native fee is bought from the pool pairing `fee_token` with the native currency right
+/// before it is charged, topped up to the existential deposit for accounts without one, spending
+/// at most `max_fee_in_token` of `fee_token`. The part of the fee refunded after dispatch is sold
+/// back for `fee_token` when the pool accepts it, otherwise it stays in the native currency.
+/// Without `fee_token` the fee is charged exactly like `ChargeTransactionPayment` does and
+/// `max_fee_in_token` is ignored.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+pub struct ChargeFeeInToken {
+	#[codec(compact)]
+	tip: Balance,
+	fee_token: Option<TokenId>,
+	#[codec(compact)]
+	max_fee_in_token: Balance,
+}
+
+impl ChargeFeeInToken {
+	pub fn new(tip: Balance, fee_token: Option<TokenId>, max_fee_in_token: Balance) -> Self {
+		Self { tip, fee_token, max_fee_in_token }
+	}
+
+	fn native(&self) -> ChargeTransactionPayment<Runtime> {
+		ChargeTransactionPayment::from(self.tip)
+	}
+
+	fn fee_pool(fee_token: TokenId) -> Result<PoolId, TransactionValidityError> {
+		Dex::get_pool_id(fee_token, NativeTokenId::get())
+			.ok_or_else(|| InvalidTransaction::Payment.into())
+	}
+
+	/// Native amount `who` has to buy to pay `fee` and stay alive.
+	fn native_needed(&self, who: &AccountId, info: &DispatchInfo, len: usize) -> Balance {
+		let fee =
+			pallet_transaction_payment::Pallet::<Runtime>::compute_fee(len as u32, info, self.tip);
+		if fee.is_zero() {
+			return fee
+		}
+		fee.saturating_add(ExistentialDeposit::get().saturating_sub(Balances::free_balance(who)))
+	}
+
+	/// Priority `ChargeTransactionPayment` gives to a transaction paying `fee`, tip included.
+	fn priority(&self, info: &DispatchInfo, len: usize, fee: Balance) -> TransactionPriority {
+		let max_block_weight = BlockWeights::get().max_block;
+		let max_block_length = *BlockLength::get().max.get(info.class) as u64;
+		let bounded_weight = info.weight.max(1).min(max_block_weight);
+		let bounded_length = (len as u64).max(1).min(max_block_length);
+		let max_tx_per_block = (max_block_weight / bounded_weight)
+			.min(max_block_length / bounded_length)
+			.saturated_into::<Balance>();
+		let scaled_tip = self.tip.saturating_add(One::one()).saturating_mul(max_tx_per_block);
+		let priority = match info.class {
+			DispatchClass::Normal | DispatchClass::Mandatory => scaled_tip,
+			DispatchClass::Operational => scaled_tip.saturating_add(
+				fee.saturating_mul(OperationalFeeMultiplier::get().into())
+					.saturating_mul(max_tx_per_block),
+			),
+		};
+		priority.saturated_into()
+	}
+}
+
+impl sp_std::fmt::Debug for ChargeFeeInToken {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(
+			f,
+			"ChargeFeeInToken<{:?}, {:?}, {:?}>",
+			self.tip, self.fee_token, self.max_fee_in_token
+		)
+	}
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		Ok(())
+	}
+}
+
+impl SignedExtension for ChargeFeeInToken {
+	const IDENTIFIER: &'static str = "ChargeFeeInToken";
+	type AccountId = AccountId;
+	type Call = Call;
+	type AdditionalSigned = ();
+	/// Also carries the signer and the pool of `fee_token` to refund the fee in it.
+	type Pre =
+		(<ChargeTransactionPayment<Runtime> as SignedExtension>::Pre, Option<(AccountId, PoolId)>);
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> TransactionValidity {
+		let fee_token = match self.fee_token {
+			Some(fee_token) => fee_token,
+			None => return self.native().validate(who, call, info, len),
+		};
+		let pool_id = Self::fee_pool(fee_token)?;
+		let fee =
+			pallet_transaction_payment::Pallet::<Runtime>::compute_fee(len as u32, info, self.tip);
+		let amount_in =
+			Dex::quote_exact_out(pool_id, fee_token, self.native_needed(who, info, len))
+				.map_err(|_| InvalidTransaction::Payment)?;
+		let balance = <Runtime as pallet_dex::Config>::Tokens::balance_of(who, fee_token)
+			.map_err(|_| InvalidTransaction::Payment)?;
+		if amount_in > self.max_fee_in_token || balance < amount_in {
+			return Err(InvalidTransaction::Payment.into())
+		}
+		Ok(ValidTransaction { priority: self.priority(info, len, fee), ..Default::default() })
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		let fee_pool = match self.fee_token {
+			Some(fee_token) => {
+				let pool_id = Self::fee_pool(fee_token)?;
+				let needed = self.native_needed(who, info, len);
+				if !needed.is_zero() {
+					Dex::swap_exact_out(who, pool_id, fee_token, needed, self.max_fee_in_token)
+						.map_err(|_| InvalidTransaction::Payment)?;
+				}
+				Some((who.clone(), pool_id))
+			},
+			None => None,
+		};
+		Ok((self.native().pre_dispatch(who, call, info, len)?, fee_pool))
+	}
+
+	fn post_dispatch(
+		(pre, fee_pool): Self::Pre,
+		info: &DispatchInfoOf<Self::Call>,
+		post_info: &PostDispatchInfoOf<Self::Call>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		let fee_pool = fee_pool.map(|(who, pool_id)| (Balances::free_balance(&who), who, pool_id));
+		ChargeTransactionPayment::<Runtime>::post_dispatch(pre, info, post_info, len, result)?;
+		if let Some((balance_before, who, pool_id)) = fee_pool {
+			let refund = Balances::free_balance(&who).saturating_sub(balance_before);
+			if !refund.is_zero() {
+				// best effort, a refund the pool does not accept stays in the native currency
+				let _ = Dex::swap_exact_in(&who, pool_id, NativeTokenId::get(), refund);
+			}
+		}
+		Ok(())
+	}
+}
@@ -31,7 +31,7 @@ pub use frame_support::{
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
 		IdentityFee, Weight,
 	},
-	StorageValue,
+	PalletId, StorageValue,
 };
 pub use pallet_balances::Call as BalancesCall;
 pub use pallet_timestamp::Call as TimestampCall;
@@ -44,6 +44,12 @@ pub use sp_runtime::{Perbill, Permill};
 pub use pallet_dex;
 pub use pallet_erc1155;
 
+mod fee_payment;
+pub use fee_payment::ChargeFeeInToken;
+
+#[cfg(test)]
+mod tests;
+
 /// An index to a block.
 pub type BlockNumber = u32;
 
@@ -99,10 +105,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 100,
+	spec_version: 101,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 };
 
 /// This determines the average expected block time that we are targeting.
@@ -276,24 +282,52 @@ impl pallet_sudo::Config for Runtime {
 }
 
 parameter_types! {
-    pub const Decimals: u32 = 6;
+    /// Decimals of the ERC1155 tokens created before they were declared per token.
+    pub const Decimals: u8 = 6;
+    pub const Erc1155PalletId: PalletId = PalletId(*b"erc/1155");
+    pub const WrappedTokenId: u32 = u32::MAX;
+    pub const StringLimit: u32 = 128;
+    pub const MaxDataLen: u32 = 256;
 }
 
 impl pallet_erc1155::Config for Runtime {
 	type Event = Event;
 	type TokenId = u32;
 	type Balance = Balance;
-	type Decimals = Decimals;
+	type Currency = Balances;
+	type PalletId = Erc1155PalletId;
+	type WrappedTokenId = WrappedTokenId;
+	type NativeTokenId = NativeTokenId;
+	type StringLimit = StringLimit;
+	type AdminOrigin = frame_system::EnsureRoot<AccountId>;
+	type MaxDataLen = MaxDataLen;
+	type TransferHook = ();
 }
 
 parameter_types! {
+    pub const NativeTokenId: u32 = 0;
     pub const Fee: Perbill = Perbill::from_percent(99); // 1% per trade
+    pub const DexPalletId: PalletId = PalletId(*b"dex/farm");
+    pub const MaxSwapIntents: u32 = 64;
+    // 1 unit with 12 decimals, forfeited by the commitments which are not revealed
+    pub const CommitmentDeposit: Balance = 1_000_000_000_000;
+    pub const RevealWindow: BlockNumber = 10;
+    pub const MaxCommitmentsPerBlock: u32 = 64;
 }
 
 impl pallet_dex::Config for Runtime {
 	type Event = Event;
-	type Tokens = Erc1155;
+	/// Pools can pair the native currency, as `NativeTokenId`, with ERC1155 tokens.
+	type Tokens = pallet_erc1155::NativeAdapter<Balances, Erc1155, NativeTokenId>;
 	type Fee = Fee;
+	type AdminOrigin = frame_system::EnsureRoot<AccountId>;
+	type PalletId = DexPalletId;
+	type PauseOrigin = frame_system::EnsureRoot<AccountId>;
+	type MaxSwapIntents = MaxSwapIntents;
+	type Currency = Balances;
+	type CommitmentDeposit = CommitmentDeposit;
+	type RevealWindow = RevealWindow;
+	type MaxCommitmentsPerBlock = MaxCommitmentsPerBlock;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -311,8 +345,9 @@ construct_runtime!(
 		Balances: pallet_balances,
 		TransactionPayment: pallet_transaction_payment,
 		Sudo: pallet_sudo,
-		Dex: pallet_dex,
-		Erc1155: pallet_erc1155,
+		// genesis pools of the DEX are funded with ERC1155 tokens, so those are built first
+		Erc1155: pallet_erc1155 = 9,
+		Dex: pallet_dex = 8,
 	}
 );
 
@@ -330,7 +365,7 @@ pub type SignedExtra = (
 	frame_system::CheckEra<Runtime>,
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
-	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	ChargeFeeInToken,
 );
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
@@ -341,6 +376,12 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
+	(
+		pallet_dex::migrations::v1::MigrateToMultiPool<Runtime>,
+		pallet_erc1155::migrations::v1::RecordTokenInfos<Runtime>,
+		pallet_erc1155::migrations::v2::RecordDecimals<Runtime, Decimals>,
+		pallet_erc1155::migrations::v3::IndexHolders<Runtime>,
+	),
 >;
 
 impl_runtime_apis! {
@@ -474,6 +515,80 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_erc1155_runtime_api::Erc1155Api<
+		Block,
+		AccountId,
+		u32,
+		Balance,
+		pallet_erc1155::TokenMetadata<Vec<u8>>,
+	> for Runtime {
+		fn metadata(token_id: u32) -> Option<pallet_erc1155::TokenMetadata<Vec<u8>>> {
+			Erc1155::metadata(token_id)
+		}
+
+		fn uri(token_id: u32) -> Vec<u8> {
+			Erc1155::uri(token_id)
+		}
+
+		fn tokens_of(account: AccountId, offset: u32, limit: u32) -> Vec<(u32, Balance)> {
+			Erc1155::tokens_of(&account, offset, limit)
+		}
+
+		fn holders_of(token_id: u32, offset: u32, limit: u32) -> Vec<(AccountId, Balance)> {
+			Erc1155::holders_of(token_id, offset, limit)
+		}
+
+		fn owner_of(token_id: u32) -> Option<AccountId> {
+			Erc1155::owner_of(token_id)
+		}
+
+		fn royalty_info(token_id: u32, sale_price: Balance) -> Option<(AccountId, Balance)> {
+			<Erc1155 as traits::Erc1155<AccountId>>::royalty_info(token_id, sale_price)
+		}
+	}
+
+	impl pallet_dex_runtime_api::DexApi<
+		Block,
+		AccountId,
+		pallet_dex::PoolId,
+		u32,
+		Balance,
+		pallet_dex::SwapQuote<Balance>,
+	> for Runtime {
+		fn quote_buy(
+			pool_id: pallet_dex::PoolId,
+			token_id: u32,
+			amount: Balance,
+		) -> Option<pallet_dex::SwapQuote<Balance>> {
+			Dex::quote_buy(pool_id, token_id, amount).ok()
+		}
+
+		fn quote_exact_out(
+			pool_id: pallet_dex::PoolId,
+			token_id: u32,
+			amount_out: Balance,
+		) -> Option<Balance> {
+			Dex::quote_exact_out(pool_id, token_id, amount_out).ok()
+		}
+
+		fn quote_deposit_single_token(
+			pool_id: pallet_dex::PoolId,
+			token_id: u32,
+			amount: Balance,
+		) -> Option<pallet_dex::SwapQuote<Balance>> {
+			Dex::quote_deposit_single_token(pool_id, token_id, amount).ok()
+		}
+
+		fn quote_withdraw_single_token(
+			pool_id: pallet_dex::PoolId,
+			owner: AccountId,
+			token_id: u32,
+			share_percent: u32,
+		) -> Option<pallet_dex::SwapQuote<Balance>> {
+			Dex::quote_withdraw_single_token(pool_id, &owner, token_id, share_percent).ok()
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (
@@ -0,0 +1,145 @@
+use crate::*;
+use frame_support::{
+	assert_ok,
+	weights::{DispatchClass, DispatchInfo, Pays, PostDispatchInfo},
+};
+use pallet_transaction_payment::ChargeTransactionPayment;
+use sp_runtime::{
+	traits::SignedExtension,
+	transaction_validity::{InvalidTransaction, TransactionValidityError},
+};
+use traits::Erc1155 as _;
+
+const UNIT: Balance = 1_000_000_000_000;
+const FEE_TOKEN: u32 = 1;
+const NO_POOL_TOKEN: u32 = 2;
+const POOL_ID: pallet_dex::PoolId = 0;
+const LEN: usize = 100;
+
+fn alice() -> AccountId {
+	AccountId::from([1; 32])
+}
+
+fn bob() -> AccountId {
+	AccountId::from([2; 32])
+}
+
+fn charlie() -> AccountId {
+	AccountId::from([3; 32])
+}
+
+fn call() -> Call {
+	Call::System(frame_system::Call::remark { remark: vec![] })
+}
+
+fn info() -> DispatchInfo {
+	DispatchInfo { weight: 10_000_000, class: DispatchClass::Normal, pays_fee: Pays::Yes }
+}
+
+fn token_balance(who: &AccountId) -> Balance {
+	Erc1155::balance_of(who, FEE_TOKEN).unwrap()
+}
+
+/// Alice provides a pool of 1000 `FEE_TOKEN` for 100 native units, Bob holds 10 `FEE_TOKEN`
+/// and no native currency.
+fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+	pallet_balances::GenesisConfig::<Runtime> { balances: vec![(alice(), 1_000 * UNIT)] }
+		.assimilate_storage(&mut t)
+		.unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Erc1155::init(
+			Origin::signed(alice()),
+			vec![FEE_TOKEN, NO_POOL_TOKEN],
+			vec![1_000_000 * UNIT, 1_000_000 * UNIT],
+			vec![12, 12]
+		));
+		assert_ok!(Erc1155::set_approval_for_all(
+			Origin::signed(alice()),
+			Dex::pool_account(POOL_ID),
+			true
+		));
+		assert_ok!(Dex::init(
+			Origin::signed(alice()),
+			FEE_TOKEN,
+			1_000 * UNIT,
+			NativeTokenId::get(),
+			100 * UNIT
+		));
+		assert_ok!(Erc1155::transfer(&alice(), &bob(), FEE_TOKEN, 10 * UNIT, &[]));
+		assert_ok!(Erc1155::transfer(&alice(), &bob(), NO_POOL_TOKEN, 10 * UNIT, &[]));
+	});
+	ext
+}
+
+#[test]
+fn fee_should_be_paid_in_token() {
+	new_test_ext().execute_with(|| {
+		let extension = ChargeFeeInToken::new(0, Some(FEE_TOKEN), UNIT);
+		assert_ok!(extension.validate(&bob(), &call(), &info(), LEN));
+		assert_ok!(extension.pre_dispatch(&bob(), &call(), &info(), LEN));
+		// the fee is bought with the token, the account is kept alive
+		assert!(token_balance(&bob()) < 10 * UNIT);
+		assert!(Balances::free_balance(&bob()) >= ExistentialDeposit::get());
+		assert!(Balances::free_balance(&bob()) < 2 * ExistentialDeposit::get());
+	});
+}
+
+#[test]
+fn unused_fee_should_be_refunded_in_token() {
+	new_test_ext().execute_with(|| {
+		let extension = ChargeFeeInToken::new(0, Some(FEE_TOKEN), UNIT);
+		let pre = extension.pre_dispatch(&bob(), &call(), &info(), LEN).unwrap();
+		let paid = 10 * UNIT - token_balance(&bob());
+		let native = Balances::free_balance(&bob());
+		// the call used none of its weight
+		let post_info = PostDispatchInfo { actual_weight: Some(0), pays_fee: Pays::Yes };
+		assert_ok!(ChargeFeeInToken::post_dispatch(pre, &info(), &post_info, LEN, &Ok(())));
+		assert!(10 * UNIT - token_balance(&bob()) < paid);
+		assert_eq!(Balances::free_balance(&bob()), native);
+	});
+}
+
+#[test]
+fn fee_without_pool_should_be_rejected() {
+	new_test_ext().execute_with(|| {
+		let extension = ChargeFeeInToken::new(0, Some(NO_POOL_TOKEN), UNIT);
+		let payment = TransactionValidityError::Invalid(InvalidTransaction::Payment);
+		assert_eq!(extension.validate(&bob(), &call(), &info(), LEN), Err(payment));
+		assert_eq!(extension.pre_dispatch(&bob(), &call(), &info(), LEN).err(), Some(payment));
+		assert_eq!(Erc1155::balance_of(&bob(), NO_POOL_TOKEN), Ok(10 * UNIT));
+	});
+}
+
+#[test]
+fn fee_above_balance_or_limit_should_be_rejected() {
+	new_test_ext().execute_with(|| {
+		let payment = TransactionValidityError::Invalid(InvalidTransaction::Payment);
+		let extension = ChargeFeeInToken::new(0, Some(FEE_TOKEN), UNIT);
+		assert_eq!(extension.validate(&charlie(), &call(), &info(), LEN), Err(payment));
+		assert_eq!(extension.pre_dispatch(&charlie(), &call(), &info(), LEN).err(), Some(payment));
+
+		let extension = ChargeFeeInToken::new(0, Some(FEE_TOKEN), 1);
+		assert_eq!(extension.validate(&bob(), &call(), &info(), LEN), Err(payment));
+		assert_eq!(extension.pre_dispatch(&bob(), &call(), &info(), LEN).err(), Some(payment));
+		assert_eq!(token_balance(&bob()), 10 * UNIT);
+	});
+}
+
+#[test]
+fn fee_in_token_should_have_native_priority() {
+	new_test_ext().execute_with(|| {
+		for class in [DispatchClass::Normal, DispatchClass::Operational] {
+			let info = DispatchInfo { class, ..info() };
+			let in_token = ChargeFeeInToken::new(5, Some(FEE_TOKEN), UNIT)
+				.validate(&bob(), &call(), &info, LEN)
+				.unwrap();
+			let native = ChargeTransactionPayment::<Runtime>::from(5)
+				.validate(&alice(), &call(), &info, LEN)
+				.unwrap();
+			assert_eq!(in_token.priority, native.priority);
+		}
+	});
+}
@@ -26,10 +26,12 @@ pub trait Erc1155<AccountId> {
 		+ Default
 		+ scale_info::TypeInfo;
 
+	/// Create `token_ids` owned by `who`, the supplies are given in base units.
 	fn init(
 		who: &AccountId,
 		token_ids: &Vec<Self::TokenId>,
 		initial_supplies: &Vec<Self::Balance>,
+		decimals: &Vec<u8>,
 	) -> DispatchResult;
 
 	fn total_supply(token_id: Self::TokenId) -> Result<Self::Balance, sp_runtime::DispatchError>;
@@ -44,11 +46,13 @@ pub trait Erc1155<AccountId> {
 		token_ids: Vec<Self::TokenId>,
 	) -> Result<Vec<Self::Balance>, sp_runtime::DispatchError>;
 
+	/// `data` is passed unchanged to the recipient, like a memo or routing information.
 	fn transfer_from_single(
 		from: &AccountId,
 		to: &AccountId,
 		token_id: &Self::TokenId,
 		amount: &Self::Balance,
+		data: &[u8],
 	) -> DispatchResult;
 
 	fn transfer_from_batch(
@@ -56,6 +60,7 @@ pub trait Erc1155<AccountId> {
 		to: &AccountId,
 		token_ids: &Vec<Self::TokenId>,
 		amounts: &Vec<Self::Balance>,
+		data: &[u8],
 	) -> DispatchResult;
 
 	fn transfer(
@@ -63,7 +68,76 @@ pub trait Erc1155<AccountId> {
 		to: &AccountId,
 		token_id: Self::TokenId,
 		amount: Self::Balance,
+		data: &[u8],
 	) -> DispatchResult;
-	
+
+	/// Create `amount` of `token_id` for `to`, callers are responsible for checking who may mint.
+	fn mint(to: &AccountId, token_id: Self::TokenId, amount: Self::Balance) -> DispatchResult;
+
+	/// Destroy `amount` of `token_id` owned by `from`.
+	fn burn(from: &AccountId, token_id: Self::TokenId, amount: Self::Balance) -> DispatchResult;
+
 	fn set_approval_for_all(owner: &AccountId, operator: &AccountId, approved: bool);
+
+	fn is_approved_for_all(owner: &AccountId, operator: &AccountId) -> bool;
+
+	/// Amount of `token_id` which `spender` may still move from `owner` without being approved
+	/// for all of its tokens.
+	fn allowance(owner: &AccountId, spender: &AccountId, token_id: Self::TokenId) -> Self::Balance;
+
+	/// Recipient and amount of the royalty owed when `token_id` is sold for `sale_price`, `None`
+	/// if the token has no royalty.
+	fn royalty_info(
+		token_id: Self::TokenId,
+		sale_price: Self::Balance,
+	) -> Option<(AccountId, Self::Balance)>;
+}
+
+/// Lets the recipients of `Erc1155` tokens react to them or reject them, like
+/// `onERC1155Received` and `onERC1155BatchReceived` do for ERC1155 contracts.
+///
+/// The hooks are called after the balances are updated, an error reverts the whole transfer.
+/// Mints are received from the zero account and burns are received by it.
+pub trait TransferHook<AccountId, TokenId, Balance> {
+	fn on_received(
+		operator: &AccountId,
+		from: &AccountId,
+		to: &AccountId,
+		token_id: TokenId,
+		amount: Balance,
+		data: &[u8],
+	) -> DispatchResult;
+
+	fn on_batch_received(
+		operator: &AccountId,
+		from: &AccountId,
+		to: &AccountId,
+		token_ids: &[TokenId],
+		amounts: &[Balance],
+		data: &[u8],
+	) -> DispatchResult;
+}
+
+impl<AccountId, TokenId, Balance> TransferHook<AccountId, TokenId, Balance> for () {
+	fn on_received(
+		_operator: &AccountId,
+		_from: &AccountId,
+		_to: &AccountId,
+		_token_id: TokenId,
+		_amount: Balance,
+		_data: &[u8],
+	) -> DispatchResult {
+		Ok(())
+	}
+
+	fn on_batch_received(
+		_operator: &AccountId,
+		_from: &AccountId,
+		_to: &AccountId,
+		_token_ids: &[TokenId],
+		_amounts: &[Balance],
+		_data: &[u8],
+	) -> DispatchResult {
+		Ok(())
+	}
 }
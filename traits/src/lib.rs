@@ -1,5 +1,5 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-pub use erc1155::Erc1155;
+pub use erc1155::{Erc1155, TransferHook};
 
 pub mod erc1155;